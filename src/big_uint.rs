@@ -0,0 +1,280 @@
+use rand::RngCore;
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Display, Formatter, Result as FmtResult},
+    ops::{Add, AddAssign, Mul, SubAssign},
+};
+
+/// An arbitrary-precision unsigned integer, for counting strings in languages too large for a [u64].
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// The digits in base 2^64, least significant first, with no trailing zeros.
+    limbs: Vec<u64>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self::from(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// The value as a [u64], if it fits.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs.as_slice() {
+            [] => Some(0),
+            &[limb] => Some(limb),
+            _ => None,
+        }
+    }
+
+    /// Divide by `divisor`, returning the quotient and remainder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divisor` is zero.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "divisor should be positive");
+
+        if self < divisor {
+            return (Self::zero(), self.clone());
+        }
+
+        let mut quotient = Self {
+            limbs: vec![0; self.limbs.len()],
+        };
+        let mut remainder = Self::zero();
+
+        // schoolbook long division in base 2, from the most significant bit down.
+        for bit in (0..64 * self.limbs.len()).rev() {
+            let (limb, offset) = (bit / 64, bit % 64);
+
+            remainder = &remainder * 2;
+            remainder += &Self::from((self.limbs[limb] >> offset) & 1);
+
+            if remainder >= *divisor {
+                remainder -= divisor;
+                quotient.limbs[limb] |= 1 << offset;
+            }
+        }
+
+        quotient.normalize();
+
+        (quotient, remainder)
+    }
+
+    /// A uniformly random integer less than `bound`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound` is zero.
+    pub fn random_below<R: RngCore + ?Sized>(bound: &Self, rng: &mut R) -> Self {
+        let &top = bound.limbs.last().expect("bound should be positive");
+
+        // mask off the unused high bits, so each candidate is below twice the bound and accepted at least half the
+        // time.
+        let mask = u64::MAX >> top.leading_zeros();
+
+        loop {
+            let mut candidate = Self {
+                limbs: (0..bound.limbs.len()).map(|_| rng.next_u64()).collect(),
+            };
+
+            *candidate
+                .limbs
+                .last_mut()
+                .expect("bound should be positive") &= mask;
+            candidate.normalize();
+
+            if candidate < *bound {
+                return candidate;
+            }
+        }
+    }
+
+    /// Remove the most significant zero limbs, keeping the representation unique.
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    /// Divide by a small divisor, returning the quotient and remainder.
+    fn div_rem_small(&self, divisor: u64) -> (Self, u64) {
+        let mut remainder = 0;
+
+        let mut limbs: Vec<_> = self
+            .limbs
+            .iter()
+            .rev()
+            .map(|&limb| {
+                let dividend = (u128::from(remainder) << 64) | u128::from(limb);
+
+                remainder = (dividend % u128::from(divisor)) as u64;
+                (dividend / u128::from(divisor)) as u64
+            })
+            .collect();
+
+        limbs.reverse();
+
+        let mut quotient = Self { limbs };
+        quotient.normalize();
+
+        (quotient, remainder)
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        let mut big = Self { limbs: vec![value] };
+        big.normalize();
+        big
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, other: &BigUint) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+
+        let mut carry = false;
+
+        for (index, limb) in self.limbs.iter_mut().enumerate() {
+            let other_limb = other.limbs.get(index).copied().unwrap_or(0);
+
+            let (sum, overflowed) = limb.overflowing_add(other_limb);
+            let (sum, carried) = sum.overflowing_add(u64::from(carry));
+
+            *limb = sum;
+            carry = overflowed || carried;
+
+            // past the end of `other`, the carry is all that's left to add.
+            if !carry && index >= other.limbs.len() {
+                break;
+            }
+        }
+
+        if carry {
+            self.limbs.push(1);
+        }
+    }
+}
+
+impl SubAssign<&BigUint> for BigUint {
+    /// # Panics
+    ///
+    /// Panics if `other` is greater than `self`.
+    fn sub_assign(&mut self, other: &BigUint) {
+        assert!(*other <= *self, "subtraction should not underflow");
+
+        let mut borrow = false;
+
+        for (index, limb) in self.limbs.iter_mut().enumerate() {
+            let other_limb = other.limbs.get(index).copied().unwrap_or(0);
+
+            let (difference, overflowed) = limb.overflowing_sub(other_limb);
+            let (difference, borrowed) = difference.overflowing_sub(u64::from(borrow));
+
+            *limb = difference;
+            borrow = overflowed || borrowed;
+
+            // past the end of `other`, the borrow is all that's left to subtract.
+            if !borrow && index >= other.limbs.len() {
+                break;
+            }
+        }
+
+        self.normalize();
+    }
+}
+
+impl Add<&BigUint> for BigUint {
+    type Output = BigUint;
+
+    fn add(mut self, other: &BigUint) -> Self::Output {
+        self += other;
+        self
+    }
+}
+
+impl Mul<u64> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, factor: u64) -> Self::Output {
+        let mut carry = 0;
+
+        let mut limbs: Vec<_> = self
+            .limbs
+            .iter()
+            .map(|&limb| {
+                let product = u128::from(limb) * u128::from(factor) + u128::from(carry);
+
+                carry = (product >> 64) as u64;
+                product as u64
+            })
+            .collect();
+
+        limbs.push(carry);
+
+        let mut product = BigUint { limbs };
+        product.normalize();
+        product
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // without trailing zeros, more limbs means a larger number.
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        /// The largest power of ten that fits in a [u64].
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+
+        // peel off 19 decimal digits at a time, least significant first.
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+
+        while !rest.is_zero() {
+            let (quotient, remainder) = rest.div_rem_small(CHUNK);
+
+            chunks.push(remainder);
+            rest = quotient;
+        }
+
+        let mut chunks = chunks.into_iter().rev();
+
+        write!(f, "{}", chunks.next().unwrap_or(0))?;
+
+        for chunk in chunks {
+            write!(f, "{chunk:019}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Debug for BigUint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Display::fmt(self, f)
+    }
+}
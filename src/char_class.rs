@@ -0,0 +1,311 @@
+use std::{
+    fmt::{Debug, Display, Formatter, Result as FmtResult},
+    ops::RangeInclusive,
+};
+
+/// A set of characters, stored as sorted, non-overlapping, non-adjacent inclusive ranges.
+///
+/// Unlike a closure, a [CharClass] can be inspected: it can be printed, intersected with other classes, and
+/// enumerated by analysis code.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct CharClass {
+    ranges: Vec<(char, char)>,
+}
+
+impl CharClass {
+    /// Create a [CharClass] from any number of (possibly overlapping, unsorted or empty) ranges.
+    pub fn new(ranges: impl IntoIterator<Item = RangeInclusive<char>>) -> Self {
+        let mut ranges: Vec<_> = ranges
+            .into_iter()
+            .map(RangeInclusive::into_inner)
+            // discard empty ranges.
+            .filter(|(start, end)| start <= end)
+            .collect();
+
+        ranges.sort_unstable();
+
+        // merge ranges that overlap or touch.
+        let ranges = ranges
+            .into_iter()
+            .fold(Vec::new(), |mut merged, (start, end)| {
+                match merged.last_mut() {
+                    Some((_, last_end))
+                        if next_char(*last_end).is_none_or(|next| start <= next) =>
+                    {
+                        *last_end = end.max(*last_end);
+                    }
+                    _ => merged.push((start, end)),
+                }
+
+                merged
+            });
+
+        Self { ranges }
+    }
+
+    /// The [CharClass] matching no characters.
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// The [CharClass] matching every character.
+    pub fn any() -> Self {
+        Self::new(['\0'..=char::MAX])
+    }
+
+    /// The [CharClass] matching a single character.
+    pub fn single(character: char) -> Self {
+        Self::new([character..=character])
+    }
+
+    /// The ASCII digits, `\d`.
+    pub fn digit() -> Self {
+        Self::new(['0'..='9'])
+    }
+
+    /// The ASCII word characters, `\w`.
+    pub fn word() -> Self {
+        Self::new(['0'..='9', 'A'..='Z', '_'..='_', 'a'..='z'])
+    }
+
+    /// The ASCII whitespace characters, `\s`.
+    pub fn whitespace() -> Self {
+        Self::new(['\t'..='\r', ' '..=' '])
+    }
+
+    /// The ranges making up the class, in ascending order.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<char>> + '_ {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+
+    /// Iterate over every character in the class, in ascending order.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.ranges().flatten()
+    }
+
+    /// The first character in the class after the character, if any.
+    pub(crate) fn next_after(&self, character: char) -> Option<char> {
+        let next = next_char(character)?;
+
+        // the first range ending at or after `next` holds the answer, if there is one.
+        let index = self.ranges.partition_point(|&(_, end)| end < next);
+
+        self.ranges.get(index).map(|&(start, _)| start.max(next))
+    }
+
+    /// Query whether the class contains the character.
+    pub fn contains(&self, character: char) -> bool {
+        self.ranges
+            // find the first range ending at or after `character`...
+            .binary_search_by(|&(_, end)| end.cmp(&character))
+            // ... which is either an exact hit on its end or the range after all ranges ending before `character`.
+            .map_or_else(
+                |index| {
+                    self.ranges
+                        .get(index)
+                        .is_some_and(|&(start, _)| start <= character)
+                },
+                |_| true,
+            )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Query whether the class contains every character.
+    pub fn is_any(&self) -> bool {
+        self.ranges == [('\0', char::MAX)]
+    }
+
+    /// The single character in the class, if there is exactly one.
+    pub fn as_single(&self) -> Option<char> {
+        match self.ranges.as_slice() {
+            &[(start, end)] if start == end => Some(start),
+            _ => None,
+        }
+    }
+
+    /// The number of characters in the class.
+    pub fn len(&self) -> usize {
+        self.ranges
+            .iter()
+            .map(|&(start, end)| count_chars(start, end))
+            .sum()
+    }
+
+    /// The `n`th character in the class in ascending order, counting from 0.
+    pub fn nth(&self, mut n: usize) -> Option<char> {
+        for mut range in self.ranges() {
+            let len = count_chars(*range.start(), *range.end());
+
+            if n < len {
+                return range.nth(n);
+            }
+
+            n -= len;
+        }
+
+        None
+    }
+
+    /// A character from the class to stand for all of it, preferring a printable ASCII one, or [None] if the class
+    /// is empty.
+    pub(crate) fn representative(&self) -> Option<char> {
+        let ascii = || self.chars().take_while(char::is_ascii);
+
+        ascii()
+            .find(char::is_ascii_alphanumeric)
+            .or_else(|| ascii().find(char::is_ascii_graphic))
+            .or_else(|| self.nth(0))
+    }
+
+    /// The class containing every character not in this class.
+    #[must_use]
+    pub fn negate(&self) -> Self {
+        let mut ranges = Vec::with_capacity(self.ranges.len() + 1);
+        let mut next_start = Some('\0');
+
+        for &(start, end) in &self.ranges {
+            // add the gap before this range.
+            if let Some(gap_start) = next_start.filter(|&gap_start| gap_start < start) {
+                ranges.push((
+                    gap_start,
+                    prev_char(start).expect("gap should not be empty"),
+                ));
+            }
+
+            next_start = next_char(end);
+        }
+
+        // add the gap after the last range.
+        if let Some(gap_start) = next_start {
+            ranges.push((gap_start, char::MAX));
+        }
+
+        Self { ranges }
+    }
+
+    /// The class containing every character in either class.
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(self.ranges().chain(other.ranges()))
+    }
+
+    /// The class containing every character in both classes.
+    #[must_use]
+    pub fn intersect(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut left, mut right) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+
+        // walk both sorted lists, advancing whichever range ends first.
+        while let (Some(&&(left_start, left_end)), Some(&&(right_start, right_end))) =
+            (left.peek(), right.peek())
+        {
+            let (start, end) = (left_start.max(right_start), left_end.min(right_end));
+
+            if start <= end {
+                ranges.push((start, end));
+            }
+
+            if left_end < right_end {
+                left.next();
+            } else {
+                right.next();
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// The class containing every character in this class but not in the other.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersect(&other.negate())
+    }
+}
+
+impl From<char> for CharClass {
+    fn from(character: char) -> Self {
+        Self::single(character)
+    }
+}
+
+impl FromIterator<char> for CharClass {
+    fn from_iter<I: IntoIterator<Item = char>>(characters: I) -> Self {
+        Self::new(
+            characters
+                .into_iter()
+                .map(|character| character..=character),
+        )
+    }
+}
+
+/// Displays the class in pattern syntax, e.g. `[a-z0-9_]`.
+impl Display for CharClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        fn write_char(f: &mut Formatter<'_>, character: char) -> FmtResult {
+            match character {
+                '\\' | ']' | '[' | '-' | '^' => write!(f, "\\{character}"),
+                character if character.is_control() => write!(f, "{}", character.escape_default()),
+                character => write!(f, "{character}"),
+            }
+        }
+
+        write!(f, "[")?;
+
+        for &(start, end) in &self.ranges {
+            write_char(f, start)?;
+
+            if start != end {
+                write!(f, "-")?;
+                write_char(f, end)?;
+            }
+        }
+
+        write!(f, "]")
+    }
+}
+
+impl Debug for CharClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Display::fmt(self, f)
+    }
+}
+
+/// The number of characters from `start` to `end` inclusive, skipping the surrogate gap.
+pub(crate) fn count_chars(start: char, end: char) -> usize {
+    if start > end {
+        return 0;
+    }
+
+    let len = u32::from(end) - u32::from(start) + 1;
+
+    // the gap holds no characters, so don't count it if the range spans it.
+    let gap = if start <= '\u{D7FF}' && end >= '\u{E000}' {
+        0x800
+    } else {
+        0
+    };
+
+    (len - gap) as usize
+}
+
+/// The character after `character`, skipping the surrogate gap.
+pub(crate) fn next_char(character: char) -> Option<char> {
+    match character {
+        '\u{D7FF}' => Some('\u{E000}'),
+        character => char::from_u32(u32::from(character) + 1),
+    }
+}
+
+/// The character before `character`, skipping the surrogate gap.
+pub(crate) fn prev_char(character: char) -> Option<char> {
+    match character {
+        '\u{E000}' => Some('\u{D7FF}'),
+        character => u32::from(character).checked_sub(1).and_then(char::from_u32),
+    }
+}
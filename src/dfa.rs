@@ -0,0 +1,803 @@
+use self::alphabet::Alphabet;
+use crate::{
+    big_uint::BigUint,
+    char_class::{count_chars, prev_char, CharClass},
+    graph::Graph,
+    state::{State, StateSet},
+};
+use rand::Rng;
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
+    iter::successors,
+};
+
+pub mod alphabet;
+pub mod lazy;
+
+/// A deterministic finite automaton with a dense transition table over the equivalence classes of its [Alphabet].
+///
+/// States are numbered densely from zero. The automaton is complete: every state has a transition for every
+/// equivalence class, with state [Dfa::DEAD] absorbing everything that can no longer match.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dfa {
+    alphabet: Alphabet,
+
+    /// The transitions, indexed by `state * alphabet.len() + class`.
+    transitions: Vec<usize>,
+
+    /// Whether each state is accepting, indexed by state.
+    accepting: Vec<bool>,
+
+    start: usize,
+}
+
+impl Dfa {
+    /// The state from which no string can match.
+    pub const DEAD: usize = 0;
+
+    /// Determinize the [Graph] by subset construction.
+    pub(crate) fn new(graph: &Graph) -> Self {
+        Self::with_alphabet(graph, Alphabet::new(graph.char_classes()))
+    }
+
+    /// Determinize the [Graph] over an alphabet that refines the [Graph]'s own character classes.
+    pub(crate) fn with_alphabet(graph: &Graph, alphabet: Alphabet) -> Self {
+        // every character in an equivalence class behaves the same, so any one can stand in for the class.
+        let representatives: Vec<_> = alphabet
+            .classes()
+            .map(|class| class.chars().next().expect("classes should not be empty"))
+            .collect();
+
+        // the dead state is the empty set of graph states.
+        let mut state_ids: HashMap<Vec<State>, usize> = HashMap::from([(Vec::new(), Self::DEAD)]);
+        let mut state_sets = vec![HashSet::new()];
+
+        // reused for every transition, so only newly discovered sets of states are allocated.
+        let mut next_states = StateSet::new(graph.state_count());
+
+        graph.follow_epsilon_rules([*graph.start()], &mut next_states);
+        let start = Self::intern(&mut state_ids, &mut state_sets, &next_states);
+
+        let mut transitions = Vec::new();
+
+        // states are numbered in the order they are discovered, so processing them in order visits each once.
+        let mut state = 0;
+
+        while state < state_sets.len() {
+            for &character in &representatives {
+                next_states.clear();
+                graph.follow_rules(
+                    state_sets[state].iter().copied(),
+                    character,
+                    &mut next_states,
+                );
+                let next = Self::intern(&mut state_ids, &mut state_sets, &next_states);
+
+                transitions.push(next);
+            }
+
+            state += 1;
+        }
+
+        let accepting = state_sets
+            .iter()
+            .map(|states| states.contains(graph.end()))
+            .collect();
+
+        Self {
+            alphabet,
+            transitions,
+            accepting,
+            start,
+        }
+    }
+
+    /// Get the id of a set of graph states, assigning the next id if the set has not been seen before.
+    fn intern(
+        state_ids: &mut HashMap<Vec<State>, usize>,
+        state_sets: &mut Vec<HashSet<State>>,
+        states: &StateSet,
+    ) -> usize {
+        let mut key: Vec<_> = states.iter().collect();
+        key.sort_unstable();
+
+        match state_ids.entry(key) {
+            Entry::Occupied(entry) => *entry.get(),
+            Entry::Vacant(entry) => {
+                let id = state_sets.len();
+
+                entry.insert(id);
+                state_sets.push(states.iter().collect());
+
+                id
+            }
+        }
+    }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// The number of states, including the dead state.
+    pub fn state_count(&self) -> usize {
+        self.accepting.len()
+    }
+
+    pub fn is_accepting(&self, state: usize) -> bool {
+        self.accepting[state]
+    }
+
+    /// The state reached from `state` by any character in the equivalence class.
+    pub fn transition(&self, state: usize, class: usize) -> usize {
+        self.transitions[state * self.alphabet.len() + class]
+    }
+
+    /// The state reached from `state` by the character.
+    pub fn next_state(&self, state: usize, character: char) -> usize {
+        self.transition(state, self.alphabet.class_of(character))
+    }
+
+    /// Query whether the [Dfa] matches the whole string.
+    pub fn matches(&self, string_to_match: &str) -> bool {
+        let end = string_to_match
+            .chars()
+            .try_fold(self.start, |state, character| {
+                // stop early once nothing can match.
+                Some(self.next_state(state, character)).filter(|&next| next != Self::DEAD)
+            });
+
+        end.is_some_and(|state| self.is_accepting(state))
+    }
+
+    /// The number of strings of exactly `len` characters matched by the [Dfa].
+    pub fn count_strings(&self, len: usize) -> BigUint {
+        self.count_by_len(len)
+            .pop()
+            .expect("there should be a count for every length")
+    }
+
+    /// The number of strings of at most `max_len` characters matched by the [Dfa].
+    pub fn count_up_to(&self, max_len: usize) -> BigUint {
+        self.count_by_len(max_len)
+            .iter()
+            .fold(BigUint::zero(), |total, count| total + count)
+    }
+
+    /// Count the strings of each length from 0 to `max_len` matched by the [Dfa].
+    ///
+    /// Each string follows exactly one path through the [Dfa], so counting the paths from the start to an accepting
+    /// state, weighted by the number of characters in each class, counts the strings without enumerating them.
+    fn count_by_len(&self, max_len: usize) -> Vec<BigUint> {
+        self.completion_counts(max_len)
+            .into_iter()
+            .map(|mut completions| completions.swap_remove(self.start))
+            .collect()
+    }
+
+    /// Draw a string of exactly `len` characters uniformly at random from those matched by the [Dfa], or [None] if
+    /// there are none.
+    pub fn sample<R: Rng + ?Sized>(&self, len: usize, rng: &mut R) -> Option<String> {
+        let completions = self.completion_counts(len);
+
+        if completions[len][self.start].is_zero() {
+            return None;
+        }
+
+        let mut state = self.start;
+        let mut string = String::with_capacity(len);
+
+        for remaining in (1..=len).rev() {
+            // choose one of the paths from here uniformly, by choosing each class in proportion to the paths through it.
+            let mut choice = BigUint::random_below(&completions[remaining][state], rng);
+
+            let (class, next_state) = (0..self.alphabet.len())
+                .map(|class| (class, self.transition(state, class)))
+                .find(|&(class, next_state)| {
+                    let paths = &completions[remaining - 1][next_state]
+                        * self.alphabet.class(class).len() as u64;
+
+                    if choice < paths {
+                        return true;
+                    }
+
+                    choice -= &paths;
+                    false
+                })
+                .expect("the choice should fall within one of the classes");
+
+            // every character in the class leads to the same state, so any of them is equally likely.
+            let class = self.alphabet.class(class);
+            let character = class
+                .nth(rng.random_range(0..class.len()))
+                .expect("the index should be within the class");
+
+            string.push(character);
+            state = next_state;
+        }
+
+        Some(string)
+    }
+
+    /// The `n`th string matched by the [Dfa] in shortlex order - shorter strings first, then by character -
+    /// counting from 0, or [None] if the [Dfa] matches `n` strings or fewer.
+    pub fn nth_string(&self, n: &BigUint) -> Option<String> {
+        // a finite language's strings are shorter than the number of states, since no path to a match can loop.
+        let max_len = self.is_finite().then_some(self.state_count());
+
+        let mut completions = Vec::new();
+        let mut n = n.clone();
+
+        // skip past every shorter string to find the length of the string.
+        let len = (0..)
+            .take_while(|&len| max_len.is_none_or(|max_len| len < max_len))
+            .find(|&len| {
+                self.extend_completions(&mut completions);
+
+                let count = &completions[len][self.start];
+
+                if n < *count {
+                    return true;
+                }
+
+                n -= count;
+                false
+            })?;
+
+        let mut state = self.start;
+        let mut string = String::with_capacity(len);
+
+        for remaining in (1..=len).rev() {
+            for (mut range, class) in self.alphabet.intervals() {
+                let next_state = self.transition(state, class);
+                let per_char = &completions[remaining - 1][next_state];
+
+                if per_char.is_zero() {
+                    continue;
+                }
+
+                let paths = per_char * count_chars(*range.start(), *range.end()) as u64;
+
+                if n >= paths {
+                    n -= &paths;
+                    continue;
+                }
+
+                // every character in the interval leads to the same number of strings.
+                let (index, rest) = n.div_rem(per_char);
+                let index = index
+                    .to_u64()
+                    .expect("the index should be within the interval");
+
+                string.push(
+                    range
+                        .nth(index as usize)
+                        .expect("the index should be within the interval"),
+                );
+
+                n = rest;
+                state = next_state;
+                break;
+            }
+        }
+
+        Some(string)
+    }
+
+    /// The position of the string in shortlex order among those matched by the [Dfa], counting from 0, or [None] if
+    /// the [Dfa] doesn't match it - the inverse of [Dfa::nth_string].
+    pub fn rank(&self, string: &str) -> Option<BigUint> {
+        if !self.matches(string) {
+            return None;
+        }
+
+        let len = string.chars().count();
+        let completions = self.completion_counts(len);
+
+        // every shorter string comes first.
+        let mut rank = completions[..len]
+            .iter()
+            .fold(BigUint::zero(), |rank, counts| rank + &counts[self.start]);
+
+        let mut state = self.start;
+
+        for (position, character) in string.chars().enumerate() {
+            let remaining = len - position;
+
+            // then every string with the same prefix and a smaller character here.
+            for (range, class) in self
+                .alphabet
+                .intervals()
+                .take_while(|(range, _)| *range.start() < character)
+            {
+                let end = prev_char(character)
+                    .expect("a character after the interval's start should have a predecessor")
+                    .min(*range.end());
+                let per_char = &completions[remaining - 1][self.transition(state, class)];
+
+                rank += &(per_char * count_chars(*range.start(), end) as u64);
+            }
+
+            state = self.next_state(state, character);
+        }
+
+        Some(rank)
+    }
+
+    /// For each length from 0 to `max_len`, the number of strings of that length leading from each state to a
+    /// match, indexed by length then state.
+    pub(crate) fn completion_counts(&self, max_len: usize) -> Vec<Vec<BigUint>> {
+        let mut completions = Vec::with_capacity(max_len + 1);
+
+        for _ in 0..=max_len {
+            self.extend_completions(&mut completions);
+        }
+
+        completions
+    }
+
+    /// Add the counts for the next length to those from [Dfa::completion_counts].
+    fn extend_completions(&self, completions: &mut Vec<Vec<BigUint>>) {
+        let counts = match completions.last() {
+            // only the accepting states have a completion of length 0: the empty string.
+            None => self
+                .accepting
+                .iter()
+                .map(|&accepting| BigUint::from(u64::from(accepting)))
+                .collect(),
+            Some(previous) => (0..self.state_count())
+                .map(|state| {
+                    self.alphabet.classes().enumerate().fold(
+                        BigUint::zero(),
+                        |count, (class, chars)| {
+                            count + &(&previous[self.transition(state, class)] * chars.len() as u64)
+                        },
+                    )
+                })
+                .collect(),
+        };
+
+        completions.push(counts);
+    }
+
+    /// Query whether the [Dfa] matches no strings at all.
+    pub fn is_empty_language(&self) -> bool {
+        !self.useful_states()[self.start]
+    }
+
+    /// Query whether the [Dfa] matches only finitely many strings.
+    pub fn is_finite(&self) -> bool {
+        self.topological_order().is_some()
+    }
+
+    /// The shortlex-least string matched by the [Dfa], or [None] if it matches no strings.
+    pub fn shortest_match(&self) -> Option<String> {
+        // a dead automaton over the same alphabet accepts nothing, so any string this accepts distinguishes them.
+        let nothing = Self {
+            alphabet: self.alphabet.clone(),
+            transitions: vec![Self::DEAD; self.alphabet.len()],
+            accepting: vec![false],
+            start: Self::DEAD,
+        };
+
+        self.shortest_distinguishing(&nothing, |accepted, _| accepted)
+    }
+
+    /// The longest string matched by the [Dfa] (the least such, character by character), or [None] if it matches
+    /// no strings or infinitely many.
+    pub fn longest_match(&self) -> Option<String> {
+        let order = self.topological_order()?;
+
+        if self.is_empty_language() {
+            return None;
+        }
+
+        let useful = self.useful_states();
+
+        // the length of the longest string accepted from each useful state, working back from the end of the order.
+        let mut longest = vec![0; self.state_count()];
+
+        for &state in order.iter().rev() {
+            longest[state] = (0..self.alphabet.len())
+                .map(|class| self.transition(state, class))
+                .filter(|&next| useful[next])
+                .map(|next| longest[next] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+
+        // follow the smallest class that keeps the string as long as possible.
+        let mut string = String::new();
+        let mut state = self.start;
+
+        while longest[state] > 0 {
+            let class = (0..self.alphabet.len())
+                .find(|&class| {
+                    let next = self.transition(state, class);
+
+                    useful[next] && longest[next] + 1 == longest[state]
+                })
+                .expect("a longest path should continue");
+
+            string.push(
+                self.alphabet
+                    .class(class)
+                    .chars()
+                    .next()
+                    .expect("classes should not be empty"),
+            );
+            state = self.transition(state, class);
+        }
+
+        Some(string)
+    }
+
+    /// Get whether each state is both reachable from the start and able to reach an accepting state.
+    fn useful_states(&self) -> Vec<bool> {
+        let class_count = self.alphabet.len();
+
+        let mut reachable = vec![false; self.state_count()];
+        reachable[self.start] = true;
+
+        let mut stack = vec![self.start];
+
+        while let Some(state) = stack.pop() {
+            for class in 0..class_count {
+                let next = self.transition(state, class);
+
+                if !reachable[next] {
+                    reachable[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+
+        let mut predecessors = vec![Vec::new(); self.state_count()];
+
+        for state in 0..self.state_count() {
+            for class in 0..class_count {
+                predecessors[self.transition(state, class)].push(state);
+            }
+        }
+
+        let mut useful: Vec<_> = (0..self.state_count())
+            .map(|state| reachable[state] && self.is_accepting(state))
+            .collect();
+        let mut stack: Vec<_> = (0..self.state_count())
+            .filter(|&state| useful[state])
+            .collect();
+
+        while let Some(state) = stack.pop() {
+            for &predecessor in &predecessors[state] {
+                if reachable[predecessor] && !useful[predecessor] {
+                    useful[predecessor] = true;
+                    stack.push(predecessor);
+                }
+            }
+        }
+
+        useful
+    }
+
+    /// Order the useful states so every transition between them goes forwards, or [None] if they form a cycle.
+    fn topological_order(&self) -> Option<Vec<usize>> {
+        let useful = self.useful_states();
+
+        let useful_transitions = |state: usize| {
+            (0..self.alphabet.len())
+                .map(move |class| self.transition(state, class))
+                .filter(|&next| useful[next])
+        };
+
+        let mut in_degrees = vec![0; self.state_count()];
+
+        for state in (0..self.state_count()).filter(|&state| useful[state]) {
+            for next in useful_transitions(state) {
+                in_degrees[next] += 1;
+            }
+        }
+
+        // repeatedly remove states with no remaining transitions into them.
+        let mut order: Vec<_> = (0..self.state_count())
+            .filter(|&state| useful[state] && in_degrees[state] == 0)
+            .collect();
+        let mut index = 0;
+
+        while index < order.len() {
+            for next in useful_transitions(order[index]) {
+                in_degrees[next] -= 1;
+
+                if in_degrees[next] == 0 {
+                    order.push(next);
+                }
+            }
+
+            index += 1;
+        }
+
+        // any state left over is on a cycle.
+        let useful_count = useful.iter().filter(|&&useful| useful).count();
+
+        (order.len() == useful_count).then_some(order)
+    }
+
+    /// Find the shortlex-least string for which `distinguishes` holds of whether each [Dfa] accepts it, by a
+    /// breadth-first search of the product of the two [Dfa]s.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [Dfa]s have different alphabets.
+    pub(crate) fn shortest_distinguishing(
+        &self,
+        other: &Self,
+        distinguishes: impl Fn(bool, bool) -> bool,
+    ) -> Option<String> {
+        assert_eq!(
+            self.alphabet, other.alphabet,
+            "DFAs should share an alphabet"
+        );
+
+        // classes are numbered in order of their smallest character, so exploring them in order and spelling each
+        // with its smallest character finds the shortlex-least string.
+        let representatives: Vec<_> = self
+            .alphabet
+            .classes()
+            .map(|class| class.chars().next().expect("classes should not be empty"))
+            .collect();
+
+        let start = (self.start, other.start);
+
+        // the pair each pair was first reached from, and by which character.
+        let mut parents = HashMap::from([(start, None)]);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(pair @ (left, right)) = queue.pop_front() {
+            if distinguishes(self.is_accepting(left), other.is_accepting(right)) {
+                // walk back to the start to spell the string.
+                let mut string: Vec<_> =
+                    successors(parents[&pair], |&(parent, _)| parents[&parent])
+                        .map(|(_, character)| character)
+                        .collect();
+
+                string.reverse();
+
+                return Some(string.into_iter().collect());
+            }
+
+            for (class, &character) in representatives.iter().enumerate() {
+                let next = (self.transition(left, class), other.transition(right, class));
+
+                parents.entry(next).or_insert_with(|| {
+                    queue.push_back(next);
+                    Some((pair, character))
+                });
+            }
+        }
+
+        None
+    }
+
+    /// Produce a [Dfa] accepting exactly the strings of characters in `alphabet` that this [Dfa] rejects.
+    ///
+    /// Every equivalence class must lie either wholly inside or wholly outside `alphabet`.
+    pub(crate) fn complement(&self, alphabet: &CharClass) -> Self {
+        let in_alphabet: Vec<_> = self
+            .alphabet
+            .classes()
+            .map(|class| {
+                debug_assert!(
+                    class.difference(alphabet).is_empty() || class.intersect(alphabet).is_empty()
+                );
+
+                class.intersect(alphabet) == *class
+            })
+            .collect();
+
+        // the old dead state now accepts, so a new dead state is needed; every old state moves up by one to make room.
+        let transitions = (0..self.alphabet.len())
+            .map(|_| Self::DEAD)
+            .chain((0..self.state_count()).flat_map(|state| {
+                in_alphabet
+                    .iter()
+                    .enumerate()
+                    .map(move |(class, &in_alphabet)| {
+                        if in_alphabet {
+                            self.transition(state, class) + 1
+                        } else {
+                            Self::DEAD
+                        }
+                    })
+            }))
+            .collect();
+
+        let accepting = [false]
+            .into_iter()
+            .chain(self.accepting.iter().map(|accepting| !accepting))
+            .collect();
+
+        Self {
+            alphabet: self.alphabet.clone(),
+            transitions,
+            accepting,
+            start: self.start + 1,
+        }
+    }
+
+    /// Produce the minimal [Dfa] accepting the same language, using Hopcroft's partition refinement.
+    ///
+    /// The result is canonical: its states are numbered in breadth-first order from the start state (after the dead
+    /// state), and equivalence classes that behave identically are merged, so two [Dfa]s accept the same language if
+    /// and only if their minimized forms are equal.
+    #[must_use]
+    pub fn minimize(&self) -> Self {
+        let block_of = self.partition();
+
+        // number blocks in breadth-first order, with the dead state's block first.
+        let mut block_ids = HashMap::from([(block_of[Self::DEAD], Self::DEAD)]);
+        let mut representatives = vec![Self::DEAD];
+
+        let start = *block_ids.entry(block_of[self.start]).or_insert_with(|| {
+            representatives.push(self.start);
+            representatives.len() - 1
+        });
+
+        let mut transitions = Vec::new();
+        let mut state = 0;
+
+        while state < representatives.len() {
+            for class in 0..self.alphabet.len() {
+                let next = self.transition(representatives[state], class);
+
+                let next = *block_ids.entry(block_of[next]).or_insert_with(|| {
+                    representatives.push(next);
+                    representatives.len() - 1
+                });
+
+                transitions.push(next);
+            }
+
+            state += 1;
+        }
+
+        let accepting = representatives
+            .iter()
+            .map(|&state| self.is_accepting(state))
+            .collect();
+
+        Self {
+            alphabet: self.alphabet.clone(),
+            transitions,
+            accepting,
+            start,
+        }
+        .merge_classes()
+    }
+
+    /// Partition the states into blocks of equivalent states, returning the block of each state.
+    fn partition(&self) -> Vec<usize> {
+        let class_count = self.alphabet.len();
+
+        // the states reaching each state by each class, indexed by `state * class_count + class`.
+        let mut predecessors = vec![Vec::new(); self.transitions.len()];
+
+        for state in 0..self.state_count() {
+            for class in 0..class_count {
+                predecessors[self.transition(state, class) * class_count + class].push(state);
+            }
+        }
+
+        // start by separating accepting from non-accepting states.
+        let mut blocks: Vec<Vec<usize>> = [false, true]
+            .into_iter()
+            .map(|accepting| {
+                (0..self.state_count())
+                    .filter(|&state| self.is_accepting(state) == accepting)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|block| !block.is_empty())
+            .collect();
+
+        let mut block_of = vec![0; self.state_count()];
+
+        for (block_id, block) in blocks.iter().enumerate() {
+            for &state in block {
+                block_of[state] = block_id;
+            }
+        }
+
+        let mut worklist: Vec<_> = (0..blocks.len()).collect();
+        let mut in_worklist = vec![true; blocks.len()];
+
+        while let Some(splitter) = worklist.pop() {
+            in_worklist[splitter] = false;
+
+            let splitter_states = blocks[splitter].clone();
+
+            for class in 0..class_count {
+                // group the states leading into the splitter by their block.
+                let mut touched: HashMap<usize, Vec<usize>> = HashMap::new();
+
+                for &state in &splitter_states {
+                    for &predecessor in &predecessors[state * class_count + class] {
+                        touched
+                            .entry(block_of[predecessor])
+                            .or_default()
+                            .push(predecessor);
+                    }
+                }
+
+                for (block_id, mut split) in touched {
+                    split.sort_unstable();
+                    split.dedup();
+
+                    // a block wholly inside the preimage is not split.
+                    if split.len() == blocks[block_id].len() {
+                        continue;
+                    }
+
+                    let new_block_id = blocks.len();
+
+                    for &state in &split {
+                        block_of[state] = new_block_id;
+                    }
+
+                    blocks[block_id].retain(|&state| block_of[state] == block_id);
+                    blocks.push(split);
+
+                    // if the old block is still waiting, both halves must be; otherwise the smaller half suffices.
+                    if in_worklist[block_id] || blocks[new_block_id].len() <= blocks[block_id].len()
+                    {
+                        worklist.push(new_block_id);
+                        in_worklist.push(true);
+                    } else {
+                        worklist.push(block_id);
+                        in_worklist[block_id] = true;
+                        in_worklist.push(false);
+                    }
+                }
+            }
+        }
+
+        block_of
+    }
+
+    /// Merge equivalence classes whose transitions are identical from every state.
+    fn merge_classes(self) -> Self {
+        let class_count = self.alphabet.len();
+
+        // group classes by their column of the transition table.
+        let mut groups: HashMap<Vec<usize>, CharClass> = HashMap::new();
+
+        for class in 0..class_count {
+            let column = (0..self.state_count())
+                .map(|state| self.transition(state, class))
+                .collect();
+
+            let group = groups.entry(column).or_default();
+            *group = group.union(self.alphabet.class(class));
+        }
+
+        let alphabet = Alphabet::new(groups.values());
+
+        let transitions = (0..self.state_count())
+            .flat_map(|state| {
+                let dfa = &self;
+
+                alphabet.classes().map(move |class| {
+                    let character = class.chars().next().expect("classes should not be empty");
+
+                    dfa.next_state(state, character)
+                })
+            })
+            .collect();
+
+        Self {
+            alphabet,
+            transitions,
+            ..self
+        }
+    }
+}
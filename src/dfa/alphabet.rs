@@ -0,0 +1,133 @@
+use crate::char_class::{next_char, prev_char, CharClass};
+use std::{collections::HashMap, ops::RangeInclusive};
+
+/// A partition of all characters into equivalence classes, such that every character in a class is matched by
+/// exactly the same [CharClass]es.
+///
+/// A [Dfa](super::Dfa) only needs one transition per equivalence class rather than one per character.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    /// The first character of each elementary interval, in ascending order, with the equivalence class of the
+    /// interval. Each interval runs up to the start of the next; the first starts at `'\0'`.
+    intervals: Vec<(char, usize)>,
+
+    /// The equivalence class of each ASCII character, for fast lookup.
+    ascii: [usize; 128],
+
+    /// The characters in each equivalence class.
+    classes: Vec<CharClass>,
+}
+
+impl Alphabet {
+    /// Partition all characters according to the given classes.
+    pub fn new<'a>(char_classes: impl IntoIterator<Item = &'a CharClass>) -> Self {
+        let char_classes: Vec<_> = char_classes.into_iter().collect();
+
+        // every class starts and ends on an interval boundary.
+        let mut starts: Vec<_> = char_classes
+            .iter()
+            .flat_map(|class| class.ranges())
+            .flat_map(|range| [Some(*range.start()), next_char(*range.end())])
+            .flatten()
+            .chain(['\0'])
+            .collect();
+
+        starts.sort_unstable();
+        starts.dedup();
+
+        // intervals with the same signature (the set of classes containing them) share an equivalence class.
+        let mut signatures = HashMap::new();
+
+        let intervals: Vec<_> = starts
+            .into_iter()
+            .map(|start| {
+                let signature: Vec<_> = char_classes
+                    .iter()
+                    .map(|class| class.contains(start))
+                    .collect();
+
+                let next_class = signatures.len();
+
+                (start, *signatures.entry(signature).or_insert(next_class))
+            })
+            .collect();
+
+        let mut alphabet = Self {
+            intervals,
+            ascii: [0; 128],
+            classes: Vec::new(),
+        };
+
+        alphabet.classes = (0..signatures.len())
+            .map(|class| {
+                CharClass::new(
+                    alphabet
+                        .intervals()
+                        .filter(|&(_, interval_class)| interval_class == class)
+                        .map(|(range, _)| range),
+                )
+            })
+            .collect();
+
+        for character in '\0'..='\x7F' {
+            alphabet.ascii[character as usize] = alphabet.lookup(character);
+        }
+
+        alphabet
+    }
+
+    /// The number of equivalence classes.
+    pub fn len(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Query whether there are no equivalence classes, which is never the case: partitioning by no classes at all
+    /// still leaves one equivalence class containing every character.
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+
+    /// The equivalence class containing the character.
+    pub fn class_of(&self, character: char) -> usize {
+        match self.ascii.get(character as usize) {
+            Some(&class) => class,
+            None => self.lookup(character),
+        }
+    }
+
+    /// The characters in the equivalence class.
+    pub fn class(&self, class: usize) -> &CharClass {
+        &self.classes[class]
+    }
+
+    /// The equivalence classes, indexed by class.
+    pub fn classes(&self) -> impl Iterator<Item = &CharClass> {
+        self.classes.iter()
+    }
+
+    /// The elementary intervals in ascending order, with the equivalence class of each.
+    pub fn intervals(&self) -> impl Iterator<Item = (RangeInclusive<char>, usize)> + '_ {
+        self.intervals
+            .iter()
+            .enumerate()
+            .map(|(index, &(start, class))| {
+                let end = self
+                    .intervals
+                    .get(index + 1)
+                    .map_or(char::MAX, |&(next_start, _)| {
+                        prev_char(next_start).expect("intervals should not be empty")
+                    });
+
+                (start..=end, class)
+            })
+    }
+
+    fn lookup(&self, character: char) -> usize {
+        // find the last interval starting at or before `character`.
+        let index = self
+            .intervals
+            .partition_point(|&(start, _)| start <= character);
+
+        self.intervals[index - 1].1
+    }
+}
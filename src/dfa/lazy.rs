@@ -0,0 +1,269 @@
+use super::alphabet::Alphabet;
+use crate::{
+    graph::{evaluate::Evaluate, Graph},
+    state::{State, StateSet},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    mem::size_of,
+};
+
+/// Options for a [LazyDfa].
+#[derive(Clone, Debug)]
+pub struct LazyDfaConfig {
+    /// The approximate number of bytes the state cache may use before it is cleared.
+    pub memory_budget: usize,
+
+    /// The number of times the cache may be cleared while matching a single string before giving up on the cache
+    /// and falling back to NFA simulation for the rest of the string.
+    pub max_cache_clears: usize,
+}
+
+impl Default for LazyDfaConfig {
+    fn default() -> Self {
+        Self {
+            memory_budget: 1 << 20,
+            max_cache_clears: 8,
+        }
+    }
+}
+
+/// A DFA that determinizes a [Graph] on demand, building only the states the input actually reaches.
+///
+/// Determinized states are cached within a memory budget. When the budget is exhausted the cache is cleared and
+/// rebuilt; if that happens too often while matching one string, the rest of the string is matched by [Evaluate]
+/// instead.
+pub struct LazyDfa<'a> {
+    graph: &'a Graph,
+
+    /// The equivalence classes of the [Graph]'s rules.
+    alphabet: Alphabet,
+
+    config: LazyDfaConfig,
+
+    cache: Cache,
+
+    /// Space for the graph states after a transition, reused so computing transitions doesn't allocate.
+    next_states: StateSet,
+
+    /// The number of times the cache has been cleared.
+    cache_clears: usize,
+
+    /// The number of strings finished by [Evaluate] after the cache thrashed.
+    fallbacks: usize,
+}
+
+impl<'a> LazyDfa<'a> {
+    pub fn new(graph: &'a Graph, config: LazyDfaConfig) -> Self {
+        let alphabet = Alphabet::new(graph.char_classes());
+        let class_count = alphabet.len();
+
+        Self {
+            graph,
+            alphabet,
+            config,
+            cache: Cache::new(class_count),
+            next_states: StateSet::new(graph.state_count()),
+            cache_clears: 0,
+            fallbacks: 0,
+        }
+    }
+
+    /// The number of determinized states currently cached.
+    pub fn cached_state_count(&self) -> usize {
+        self.cache.state_sets.len()
+    }
+
+    /// The number of times the cache has been cleared.
+    pub fn cache_clears(&self) -> usize {
+        self.cache_clears
+    }
+
+    /// The number of strings finished by [Evaluate] after the cache thrashed.
+    pub fn fallbacks(&self) -> usize {
+        self.fallbacks
+    }
+
+    /// Query whether the [Graph] is able to match the string.
+    pub fn matches(&mut self, string_to_match: &str) -> bool {
+        let Self {
+            graph,
+            alphabet,
+            config,
+            cache,
+            next_states,
+            cache_clears,
+            fallbacks,
+        } = self;
+
+        let mut clears_this_string = 0;
+
+        let mut state = match cache.start {
+            Some(start) => start,
+            None => {
+                next_states.clear();
+                graph.follow_epsilon_rules([*graph.start()], next_states);
+                let start = cache.intern(Cache::key(next_states.iter()));
+
+                cache.start = Some(start);
+                start
+            }
+        };
+
+        for (index, character) in string_to_match.char_indices() {
+            let class = alphabet.class_of(character);
+
+            let next = match cache.transition(state, class) {
+                Some(next) => next,
+                None => {
+                    let representative = alphabet
+                        .class(class)
+                        .chars()
+                        .next()
+                        .expect("classes should not be empty");
+
+                    next_states.clear();
+                    graph.follow_rules(
+                        cache.state_sets[state].iter().copied(),
+                        representative,
+                        next_states,
+                    );
+
+                    let key = Cache::key(next_states.iter());
+
+                    // only a state not yet cached needs room.
+                    let next = match cache.get(&key) {
+                        Some(next) => next,
+                        None => {
+                            // make room for the new state, keeping the current one so it can be linked to the new one.
+                            if cache.memory_usage + Cache::state_size(alphabet.len(), key.len())
+                                > config.memory_budget
+                            {
+                                if clears_this_string == config.max_cache_clears {
+                                    // the cache is thrashing - finish the string by simulating the graph directly.
+                                    *fallbacks += 1;
+
+                                    let rest = &string_to_match[index + character.len_utf8()..];
+
+                                    return !next_states.is_empty()
+                                        && rest
+                                            .chars()
+                                            .try_fold(
+                                                Evaluate::from_states(graph, next_states.iter()),
+                                                Evaluate::try_follow_rules,
+                                            )
+                                            .is_some_and(|result| result.is_in_end_state());
+                                }
+
+                                clears_this_string += 1;
+                                *cache_clears += 1;
+
+                                let current_states = cache.state_sets[state].clone();
+
+                                cache.clear();
+                                state = cache.intern(Cache::key(current_states));
+                            }
+
+                            cache.intern(key)
+                        }
+                    };
+
+                    cache.set_transition(state, class, next);
+
+                    next
+                }
+            };
+
+            // stop early once nothing can match.
+            if cache.state_sets[next].is_empty() {
+                return false;
+            }
+
+            state = next;
+        }
+
+        cache.state_sets[state].contains(graph.end())
+    }
+}
+
+/// The determinized states built so far, and the transitions between them that have been followed.
+struct Cache {
+    class_count: usize,
+
+    ids: HashMap<Vec<State>, usize>,
+
+    state_sets: Vec<HashSet<State>>,
+
+    /// The transitions, indexed by `state * class_count + class`, or [None] if not yet computed.
+    transitions: Vec<Option<usize>>,
+
+    /// The id of the start state, if it is cached.
+    start: Option<usize>,
+
+    /// The approximate number of bytes used by the cached states.
+    memory_usage: usize,
+}
+
+impl Cache {
+    fn new(class_count: usize) -> Self {
+        Self {
+            class_count,
+            ids: HashMap::new(),
+            state_sets: Vec::new(),
+            transitions: Vec::new(),
+            start: None,
+            memory_usage: 0,
+        }
+    }
+
+    /// The approximate number of bytes needed to cache a state.
+    fn state_size(class_count: usize, state_count: usize) -> usize {
+        // a row of transitions, plus the set of graph states stored both as a key and as a set.
+        class_count * size_of::<Option<usize>>() + 2 * state_count * size_of::<State>()
+    }
+
+    fn clear(&mut self) {
+        self.ids.clear();
+        self.state_sets.clear();
+        self.transitions.clear();
+        self.start = None;
+        self.memory_usage = 0;
+    }
+
+    fn transition(&self, state: usize, class: usize) -> Option<usize> {
+        self.transitions[state * self.class_count + class]
+    }
+
+    fn set_transition(&mut self, state: usize, class: usize, next: usize) {
+        self.transitions[state * self.class_count + class] = Some(next);
+    }
+
+    /// The key identifying a set of graph states: its states in order.
+    fn key(states: impl IntoIterator<Item = State>) -> Vec<State> {
+        let mut key: Vec<_> = states.into_iter().collect();
+        key.sort_unstable();
+
+        key
+    }
+
+    /// Get the id of a set of graph states by its key, if it is cached.
+    fn get(&self, key: &[State]) -> Option<usize> {
+        self.ids.get(key).copied()
+    }
+
+    /// Get the id of a set of graph states by its key, caching it if it has not been seen before.
+    fn intern(&mut self, key: Vec<State>) -> usize {
+        if let Some(id) = self.get(&key) {
+            return id;
+        }
+
+        let id = self.state_sets.len();
+
+        self.memory_usage += Self::state_size(self.class_count, key.len());
+        self.state_sets.push(key.iter().copied().collect());
+        self.ids.insert(key, id);
+        self.transitions.extend((0..self.class_count).map(|_| None));
+
+        id
+    }
+}
@@ -1,115 +1,375 @@
-use self::{evaluate::Evaluate, produce_strings::ProduceStrings};
-use crate::{rule::Rule, state::State};
+use self::{
+    compare::Counterexample,
+    cover::Coverage,
+    evaluate::Evaluate,
+    parse::{ParseError, Parser},
+    produce_non_matching::ProduceNonMatching,
+    produce_strings::{Order, ProduceStrings},
+    replace::Replacer,
+    rule_index::RuleIndex,
+    search::{Captures, CapturesIter, FindIter, Match, PikeVm},
+    split::{Split, SplitInclusive, SplitN},
+};
+use crate::{
+    big_uint::BigUint,
+    char_class::CharClass,
+    dfa::{
+        alphabet::Alphabet,
+        lazy::{LazyDfa, LazyDfaConfig},
+        Dfa,
+    },
+    rule::Rule,
+    state::{State, StateSet},
+};
+use rand::Rng;
 use std::{
-    collections::HashSet,
+    cell::OnceCell,
+    collections::{HashMap, VecDeque},
     fmt::{Debug, Formatter, Result as FmtResult},
     iter::empty,
+    str::FromStr,
 };
 
+pub mod compare;
+pub mod cover;
 pub mod evaluate;
+pub mod parse;
+pub mod produce_non_matching;
 pub mod produce_strings;
+pub mod replace;
+mod rule_index;
+pub mod search;
+pub mod split;
 
+#[derive(Clone)]
 pub struct Graph {
     label: String,
+
+    /// The number of states, which are numbered densely from 0.
+    state_count: usize,
+
     start: State,
     end: State,
     rules: Vec<Rule>,
+
+    /// The capture groups, in the order their opening parentheses would appear in the pattern.
+    groups: Vec<Group>,
+
+    /// The rules indexed by state, built the first time rules are followed rather than for every intermediate graph
+    /// while combining.
+    rule_index: OnceCell<RuleIndex>,
+}
+
+/// A capture group, delimited by the states entered at its start and end.
+///
+/// A repeated group has a copy of its boundaries for each repetition, all capturing into the same group.
+#[derive(Clone)]
+struct Group {
+    name: Option<String>,
+    boundaries: Vec<(State, State)>,
 }
 
 impl Graph {
-    fn new(label: String, start: State, end: State, rules: impl IntoIterator<Item = Rule>) -> Self {
+    fn new(
+        label: String,
+        state_count: usize,
+        start: State,
+        end: State,
+        rules: impl IntoIterator<Item = Rule>,
+        groups: impl IntoIterator<Item = Group>,
+    ) -> Self {
         Self {
             label,
+            state_count,
             start,
             end,
             rules: rules.into_iter().collect(),
+            groups: groups.into_iter().collect(),
+            rule_index: OnceCell::new(),
         }
     }
 
+    /// Parse a pattern string into a [Graph], or a [ParseError] pointing at the offending part of the pattern.
+    ///
+    /// Supports literals, `.`, alternation (`|`), the quantifiers `*`, `+`, `?`, `{n}`, `{n,}` and `{n,m}`, capture
+    /// groups (`(...)`, named `(?<name>...)` or `(?P<name>...)`), non-capturing groups (`(?:...)`), character classes
+    /// (`[a-z0-9_]`, `[^abc]`), and escapes (`\n`, `\r`, `\t`, `\0`, the ASCII classes `\d`, `\w`, `\s` and their
+    /// negations `\D`, `\W`, `\S`, and `\` followed by any ASCII punctuation).
+    pub fn parse(pattern: &str) -> Result<Self, ParseError> {
+        Parser::new(pattern).parse()
+    }
+
+    /// A [Graph] matching only the empty string.
+    pub fn empty() -> Self {
+        let label = String::new();
+
+        let (start, end) = (State::new(0), State::new(1));
+
+        let rules = [
+            // add rule skipping straight to the end.
+            Rule::epsilon(start, end),
+        ];
+
+        Self::new(label, 2, start, end, rules, [])
+    }
+
+    /// A [Graph] matching any single character in the class.
+    pub fn class(class: CharClass) -> Self {
+        let label = class.to_string();
+
+        let (start, end) = (State::new(0), State::new(1));
+
+        let rules = [
+            // add rule matching any character in the class.
+            Rule::class(start, end, class),
+        ];
+
+        Self::new(label, 2, start, end, rules, [])
+    }
+
     pub fn any() -> Self {
         let label = ".".to_owned();
 
-        let start = State::new();
-        let end = State::new();
+        let (start, end) = (State::new(0), State::new(1));
 
         let rules = [
-            // add lambda rule matching any character.
+            // add rule matching any character.
             Rule::match_any(start, end),
         ];
 
-        Self::new(label, start, end, rules)
+        Self::new(label, 2, start, end, rules, [])
     }
 
     #[must_use]
     pub fn zero_or_more(self) -> Self {
         let label = format!("({})*", self.label);
 
-        let start = State::new();
-        let end = State::new();
+        // add the new states after those in self.
+        let state_count = self.state_count + 2;
+        let (start, end) = (
+            State::new(self.state_count),
+            State::new(self.state_count + 1),
+        );
 
         let rules = empty()
             // include all rules in self.
             .chain(self.rules)
+            // rules are listed in order of preference when searching, so repetition is greedy.
             .chain([
-                // add rule skipping self.
-                Rule::epsilon(start, end),
                 // add path through self.
                 Rule::epsilon(start, self.start),
-                Rule::epsilon(self.end, end),
                 // add path repeating self.
                 Rule::epsilon(self.end, self.start),
+                Rule::epsilon(self.end, end),
+                // add rule skipping self.
+                Rule::epsilon(start, end),
             ]);
 
-        Self::new(label, start, end, rules)
+        Self::new(label, state_count, start, end, rules, self.groups)
     }
 
     #[must_use]
     pub fn one_or_more(self) -> Self {
         let label = format!("({})+", self.label);
 
-        let start = State::new();
-        let end = State::new();
+        // add the new states after those in self.
+        let state_count = self.state_count + 2;
+        let (start, end) = (
+            State::new(self.state_count),
+            State::new(self.state_count + 1),
+        );
 
         let rules = empty()
             // include all rules in self.
             .chain(self.rules)
+            // rules are listed in order of preference when searching, so repetition is greedy.
             .chain([
                 // add path into self.
                 Rule::epsilon(start, self.start),
-                // add path out of self.
-                Rule::epsilon(self.end, end),
                 // add path repeating self.
                 Rule::epsilon(self.end, self.start),
+                // add path out of self.
+                Rule::epsilon(self.end, end),
             ]);
 
-        Self::new(label, start, end, rules)
+        Self::new(label, state_count, start, end, rules, self.groups)
     }
 
     #[must_use]
     pub fn optional(self) -> Self {
         let label = format!("({})?", self.label);
 
-        let start = State::new();
-        let end = State::new();
+        // add the new states after those in self.
+        let state_count = self.state_count + 2;
+        let (start, end) = (
+            State::new(self.state_count),
+            State::new(self.state_count + 1),
+        );
 
         let rules = empty()
             // include all rules in self.
             .chain(self.rules)
+            // rules are listed in order of preference when searching, so the option is greedy.
             .chain([
-                // add path skipping self.
-                Rule::epsilon(start, end),
                 // add path through self.
                 Rule::epsilon(start, self.start),
                 Rule::epsilon(self.end, end),
+                // add path skipping self.
+                Rule::epsilon(start, end),
             ]);
 
-        Self::new(label, start, end, rules)
+        Self::new(label, state_count, start, end, rules, self.groups)
+    }
+
+    /// Repeat the [Graph] at least `min` times and at most `max` times, or without limit if `max` is [None].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is less than `min`.
+    #[must_use]
+    pub fn repeat(self, min: usize, max: Option<usize>) -> Self {
+        let label = match max {
+            Some(max) if max == min => format!("({}){{{min}}}", self.label),
+            Some(max) => format!("({}){{{min},{max}}}", self.label),
+            None => format!("({}){{{min},}}", self.label),
+        };
+
+        // an unbounded repetition needs one more copy to repeat indefinitely.
+        let copy_count = match max {
+            Some(max) => {
+                assert!(min <= max, "repetition minimum {min} exceeds maximum {max}");
+                max
+            }
+            None => min + 1,
+        };
+
+        if copy_count == 0 {
+            // keep the groups, so the ones after them keep their numbers, but they can never capture anything.
+            let groups = self
+                .groups
+                .into_iter()
+                .map(|group| Group {
+                    boundaries: Vec::new(),
+                    ..group
+                })
+                .collect();
+
+            return Self {
+                label,
+                groups,
+                ..Self::empty()
+            };
+        }
+
+        let mut state_count = 0;
+        let mut rules = Vec::with_capacity(copy_count * (self.rules.len() + 3));
+
+        // every copy of a group captures into the same group.
+        let mut groups: Vec<_> = self
+            .groups
+            .iter()
+            .map(|group| Group {
+                name: group.name.clone(),
+                boundaries: Vec::new(),
+            })
+            .collect();
+
+        // add a copy of self numbered after the states so far, so each copy is renumbered only once.
+        let mut add_copy = |rules: &mut Vec<Rule>, state_count: &mut usize| {
+            let offset = *state_count;
+            *state_count += self.state_count;
+
+            rules.extend(self.rules.iter().map(|rule| {
+                rule.with_states(rule.start().shifted(offset), rule.end().shifted(offset))
+            }));
+
+            for (group, copied) in groups.iter_mut().zip(&self.groups) {
+                group.boundaries.extend(
+                    copied
+                        .boundaries
+                        .iter()
+                        .map(|&(start, end)| (start.shifted(offset), end.shifted(offset))),
+                );
+            }
+
+            (self.start.shifted(offset), self.end.shifted(offset))
+        };
+
+        let add_state = |state_count: &mut usize| {
+            *state_count += 1;
+            State::new(*state_count - 1)
+        };
+
+        // the parts of the repetition, in order, as their start and end states.
+        let mut parts = Vec::new();
+
+        // the first `min` copies are required.
+        for _ in 0..min {
+            parts.push(add_copy(&mut rules, &mut state_count));
+        }
+
+        match max {
+            // the next copy is repeated indefinitely, as by `zero_or_more`.
+            None => {
+                let (copy_start, copy_end) = add_copy(&mut rules, &mut state_count);
+                let (start, end) = (add_state(&mut state_count), add_state(&mut state_count));
+
+                // rules are listed in order of preference when searching, so repetition is greedy.
+                rules.extend([
+                    Rule::epsilon(start, copy_start),
+                    Rule::epsilon(copy_end, copy_start),
+                    Rule::epsilon(copy_end, end),
+                    Rule::epsilon(start, end),
+                ]);
+
+                parts.push((start, end));
+            }
+            // each extra copy is only tried after the one before it, and any of them may skip to the end.
+            Some(max) if max > min => {
+                let end = add_state(&mut state_count);
+                let mut next = end;
+
+                // build the copies back to front, so each knows where to continue.
+                for _ in min..max {
+                    let (copy_start, copy_end) = add_copy(&mut rules, &mut state_count);
+                    let start = add_state(&mut state_count);
+
+                    // prefer taking the copy, so the option is greedy.
+                    rules.extend([
+                        Rule::epsilon(start, copy_start),
+                        Rule::epsilon(copy_end, next),
+                        Rule::epsilon(start, end),
+                    ]);
+
+                    next = start;
+                }
+
+                parts.push((next, end));
+            }
+            Some(_) => {}
+        }
+
+        // link each part to the next.
+        rules.extend(
+            parts
+                .windows(2)
+                .map(|pair| Rule::epsilon(pair[0].1, pair[1].0)),
+        );
+
+        let (start, _) = parts[0];
+        let (_, end) = parts[parts.len() - 1];
+
+        Self::new(label, state_count, start, end, rules, groups)
     }
 
     #[must_use]
     pub fn concat(self, other: Self) -> Self {
         let label = format!("{}{}", self.label, other.label);
 
+        // number the states in other after those in self.
+        let other = other.shifted(self.state_count);
+        let state_count = other.state_count;
+
         let start = self.start;
         let end = other.end;
 
@@ -123,7 +383,12 @@ impl Graph {
                 Rule::epsilon(self.end, other.start),
             ]);
 
-        Self::new(label, start, end, rules)
+        let groups = empty()
+            // include all groups in self, then all groups in other.
+            .chain(self.groups)
+            .chain(other.groups);
+
+        Self::new(label, state_count, start, end, rules, groups)
     }
 
     #[must_use]
@@ -135,8 +400,13 @@ impl Graph {
     pub fn union(self, other: Self) -> Self {
         let label = format!("({}|{})", self.label, other.label);
 
-        let start = State::new();
-        let end = State::new();
+        // number the states in other after those in self, and add the new states after both.
+        let other = other.shifted(self.state_count);
+        let state_count = other.state_count + 2;
+        let (start, end) = (
+            State::new(other.state_count),
+            State::new(other.state_count + 1),
+        );
 
         let rules = empty()
             // include all rules in self.
@@ -152,7 +422,141 @@ impl Graph {
                 Rule::epsilon(other.end, end),
             ]);
 
-        Self::new(label, start, end, rules)
+        let groups = empty()
+            // include all groups in self, then all groups in other.
+            .chain(self.groups)
+            .chain(other.groups);
+
+        Self::new(label, state_count, start, end, rules, groups)
+    }
+
+    /// A [Graph] matching the strings matched by both graphs, built as the product of their states.
+    ///
+    /// Capture groups are not preserved, since a state of the product belongs to a group in each graph.
+    #[must_use]
+    pub fn intersect(self, other: Self) -> Self {
+        let label = format!("({}&{})", self.label, other.label);
+
+        /// Get the product state for a pair of states, numbered in order of discovery and queued to be explored if it
+        /// is new.
+        fn product_state(
+            product_states: &mut HashMap<(State, State), State>,
+            queue: &mut VecDeque<((State, State), State)>,
+            pair: (State, State),
+        ) -> State {
+            let next_state = State::new(product_states.len());
+
+            *product_states.entry(pair).or_insert_with(|| {
+                queue.push_back((pair, next_state));
+                next_state
+            })
+        }
+
+        let mut product_states = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        let start = product_state(&mut product_states, &mut queue, (self.start, other.start));
+        let end = product_state(&mut product_states, &mut queue, (self.end, other.end));
+
+        let mut rules = Vec::new();
+
+        while let Some(((left, right), from)) = queue.pop_front() {
+            // both graphs must consume the same character together.
+            for left_rule in self.consuming_rules_from(left) {
+                for right_rule in other.consuming_rules_from(right) {
+                    let pair = (left_rule.end(), right_rule.end());
+
+                    // only create the product state if some character can reach it.
+                    if let Some(rule) = left_rule.intersect(right_rule, from, from) {
+                        let to = product_state(&mut product_states, &mut queue, pair);
+
+                        rules.push(rule.with_states(from, to));
+                    }
+                }
+            }
+
+            // either graph may follow an epsilon rule while the other stays put.
+            for left_rule in self.epsilon_rules_from(left) {
+                let to = product_state(&mut product_states, &mut queue, (left_rule.end(), right));
+
+                rules.push(Rule::epsilon(from, to));
+            }
+
+            for right_rule in other.epsilon_rules_from(right) {
+                let to = product_state(&mut product_states, &mut queue, (left, right_rule.end()));
+
+                rules.push(Rule::epsilon(from, to));
+            }
+        }
+
+        Self::new(label, product_states.len(), start, end, rules, [])
+    }
+
+    /// A [Graph] matching every string of characters in `alphabet` that this [Graph] does not match.
+    ///
+    /// Capture groups are not preserved.
+    #[must_use]
+    pub fn complement(&self, alphabet: &CharClass) -> Self {
+        let label = format!("!({})", self.label);
+
+        // refine the graph's own classes so every class lies wholly inside or outside the alphabet.
+        let partition = Alphabet::new(self.char_classes().into_iter().chain([alphabet]));
+
+        let dfa = Dfa::with_alphabet(self, partition).complement(alphabet);
+
+        Self::from_dfa(label, &dfa.minimize())
+    }
+
+    /// A [Graph] matching the strings matched by this [Graph] but not by the other.
+    ///
+    /// Capture groups are not preserved.
+    #[must_use]
+    pub fn difference(self, other: Self) -> Self {
+        let label = format!("({}-{})", self.label, other.label);
+
+        Self {
+            label,
+            ..self.intersect(other.complement(&CharClass::any()))
+        }
+    }
+
+    /// Mark the [Graph] as a capture group, optionally named, so searches report where it matched.
+    ///
+    /// Groups are numbered from 1 in the order their opening parentheses would appear in the pattern; group 0 is the
+    /// whole match.
+    #[must_use]
+    pub fn group(self, name: Option<&str>) -> Self {
+        let label = match name {
+            Some(name) => format!("(?<{name}>{})", self.label),
+            None => format!("({})", self.label),
+        };
+
+        // add the new states after those in self.
+        let state_count = self.state_count + 2;
+        let (start, end) = (
+            State::new(self.state_count),
+            State::new(self.state_count + 1),
+        );
+
+        let rules = empty()
+            // include all rules in self.
+            .chain(self.rules)
+            .chain([
+                // add path into self, marking the start of the group.
+                Rule::named_epsilon(start, self.start, format!("{}(", name.unwrap_or_default())),
+                // add path out of self, marking the end of the group.
+                Rule::named_epsilon(self.end, end, format!("){}", name.unwrap_or_default())),
+            ]);
+
+        let groups = empty()
+            // the new group opens before any group in self.
+            .chain([Group {
+                name: name.map(str::to_owned),
+                boundaries: vec![(start, end)],
+            }])
+            .chain(self.groups);
+
+        Self::new(label, state_count, start, end, rules, groups)
     }
 
     /// The label of the [Graph]
@@ -168,6 +572,11 @@ impl Graph {
         &self.end
     }
 
+    /// The number of states, which are numbered densely from 0.
+    pub fn state_count(&self) -> usize {
+        self.state_count
+    }
+
     pub fn rules(&self) -> impl Iterator<Item = &Rule> {
         self.rules.iter()
     }
@@ -184,93 +593,424 @@ impl Graph {
             .is_some_and(|result| result.is_in_end_state())
     }
 
-    pub fn produce_strings<'a>(&'a self, max_len: usize) -> ProduceStrings<'a> {
-        ProduceStrings::new(self, max_len)
+    /// Find the leftmost-first match of the [Graph] anywhere in the haystack.
+    ///
+    /// Of the matches starting furthest left, the one preferred by the pattern is returned: alternatives are tried
+    /// left to right, and quantifiers are greedy.
+    pub fn find<'h>(&self, haystack: &'h str) -> Option<Match<'h>> {
+        PikeVm::new(self).find_at(haystack, 0)
     }
 
-    /// Get the set of states reachable by any number of epsilon rules (including zero) in [Graph]'s, starting from any state in the starting states.
-    fn follow_epsilon_rules(&self, start_states: HashSet<State>) -> HashSet<State> {
-        fn follow_epsilons_impl(rules: Vec<&Rule>, states: HashSet<State>) -> HashSet<State> {
-            // filter for rules that end outside `states`.
-            let rules: Vec<_> = rules
-                .into_iter()
-                .filter(|rule| !states.contains(&rule.end()))
-                .collect();
+    /// Iterate over the successive non-overlapping leftmost-first matches of the [Graph] in the haystack.
+    pub fn find_iter<'g, 'h>(&'g self, haystack: &'h str) -> FindIter<'g, 'h> {
+        FindIter::new(self, haystack)
+    }
 
-            let next_states: HashSet<_> = rules
-                .iter()
-                // filter for rules that start inside `states`.
-                .filter(|rule| states.contains(&rule.start()))
-                // map rules to end states.
-                .map(|rule| rule.end())
-                .collect();
+    /// Find the leftmost-first match of the [Graph] in the haystack, with the positions of its capture groups.
+    pub fn captures<'g, 'h>(&'g self, haystack: &'h str) -> Option<Captures<'g, 'h>> {
+        PikeVm::capturing(self).captures_at(haystack, 0)
+    }
 
-            if next_states.is_empty() {
-                states
-            } else {
-                states
-                    .into_iter()
-                    .chain(follow_epsilons_impl(rules, next_states))
-                    .collect()
+    /// Iterate over the capture groups of the successive non-overlapping leftmost-first matches of the [Graph] in
+    /// the haystack.
+    pub fn captures_iter<'g, 'h>(&'g self, haystack: &'h str) -> CapturesIter<'g, 'h> {
+        CapturesIter::new(self, haystack)
+    }
+
+    /// Replace the leftmost-first match in the haystack, if there is one.
+    ///
+    /// The replacement can be a template such as `"$2-$1"` or `"${name}"` (see [Captures::expand]), or a closure
+    /// computing the replacement from the [Captures].
+    pub fn replace(&self, haystack: &str, replacement: impl Replacer) -> String {
+        self.replacen(haystack, 1, replacement)
+    }
+
+    /// Replace every non-overlapping match in the haystack.
+    pub fn replace_all(&self, haystack: &str, replacement: impl Replacer) -> String {
+        self.replacen(haystack, usize::MAX, replacement)
+    }
+
+    /// Replace at most `limit` non-overlapping matches in the haystack, leftmost first.
+    pub fn replacen(&self, haystack: &str, limit: usize, mut replacement: impl Replacer) -> String {
+        let mut replaced = String::with_capacity(haystack.len());
+        let mut last_end = 0;
+
+        for captures in self.captures_iter(haystack).take(limit) {
+            let whole = captures.get(0).expect("group 0 should always be present");
+
+            // keep the text between matches, then replace the match.
+            replaced.push_str(&haystack[last_end..whole.start()]);
+            replacement.replace_append(&captures, &mut replaced);
+
+            last_end = whole.end();
+        }
+
+        replaced.push_str(&haystack[last_end..]);
+
+        replaced
+    }
+
+    /// Iterate over the text between the non-overlapping matches of the [Graph] in the haystack.
+    ///
+    /// Like [str::split], a match at the start or end of the haystack produces an empty piece there.
+    pub fn split<'g, 'h>(&'g self, haystack: &'h str) -> Split<'g, 'h> {
+        Split::new(self.find_iter(haystack), haystack)
+    }
+
+    /// Iterate over at most `limit` pieces of text between the matches of the [Graph], the last piece holding the
+    /// rest of the haystack.
+    pub fn splitn<'g, 'h>(&'g self, haystack: &'h str, limit: usize) -> SplitN<'g, 'h> {
+        SplitN::new(self.split(haystack), limit)
+    }
+
+    /// Iterate over the text between the matches of the [Graph], with each piece including the match that ends it.
+    pub fn split_inclusive<'g, 'h>(&'g self, haystack: &'h str) -> SplitInclusive<'g, 'h> {
+        SplitInclusive::new(self.split(haystack))
+    }
+
+    /// Determinize the [Graph] into a [Dfa], so that matching costs one table lookup per character.
+    pub fn to_dfa(&self) -> Dfa {
+        Dfa::new(self)
+    }
+
+    /// Create a [LazyDfa] that determinizes the [Graph] only as input demands, for patterns whose full [Dfa] would
+    /// be too large.
+    pub fn to_lazy_dfa(&self, config: LazyDfaConfig) -> LazyDfa<'_> {
+        LazyDfa::new(self, config)
+    }
+
+    /// The number of strings of exactly `len` characters matched by the [Graph], counted without enumerating them.
+    ///
+    /// Every character is counted, so e.g. `.` matches 1,112,064 strings of length 1.
+    pub fn count_strings(&self, len: usize) -> BigUint {
+        self.to_dfa().minimize().count_strings(len)
+    }
+
+    /// The number of strings of at most `max_len` characters matched by the [Graph], counted without enumerating
+    /// them.
+    pub fn count_up_to(&self, max_len: usize) -> BigUint {
+        self.to_dfa().minimize().count_up_to(max_len)
+    }
+
+    /// Draw a string of exactly `len` characters uniformly at random from those matched by the [Graph], or [None] if
+    /// there are none.
+    ///
+    /// Every matching string is equally likely, however many ways the pattern can match it - pass a seeded `rng` for
+    /// reproducible samples.
+    pub fn sample<R: Rng + ?Sized>(&self, len: usize, rng: &mut R) -> Option<String> {
+        self.to_dfa().minimize().sample(len, rng)
+    }
+
+    /// The `n`th string matched by the [Graph] in shortlex order - shorter strings first, then by character -
+    /// counting from 0, or [None] if the [Graph] matches `n` strings or fewer.
+    ///
+    /// Together with [Graph::rank], this numbers the matching strings, e.g. to split their generation into index
+    /// ranges. Each call builds a [Dfa], so when ranking many strings, build one with [Graph::to_dfa] and use
+    /// [Dfa::nth_string] instead.
+    pub fn nth_string(&self, n: &BigUint) -> Option<String> {
+        self.to_dfa().minimize().nth_string(n)
+    }
+
+    /// The position of the string in shortlex order among those matched by the [Graph], counting from 0, or [None]
+    /// if the [Graph] doesn't match it - the inverse of [Graph::nth_string].
+    pub fn rank(&self, string: &str) -> Option<BigUint> {
+        self.to_dfa().minimize().rank(string)
+    }
+
+    /// Query whether the [Graph] matches no strings at all.
+    pub fn is_empty_language(&self) -> bool {
+        self.to_dfa().is_empty_language()
+    }
+
+    /// Query whether the [Graph] matches only finitely many strings.
+    pub fn is_finite(&self) -> bool {
+        self.to_dfa().is_finite()
+    }
+
+    /// The shortest string matched by the [Graph] (the least such, character by character), or [None] if it matches
+    /// no strings.
+    pub fn shortest_match(&self) -> Option<String> {
+        self.to_dfa().shortest_match()
+    }
+
+    /// The longest string matched by the [Graph] (the least such, character by character), or [None] if it matches
+    /// no strings or infinitely many.
+    pub fn longest_match(&self) -> Option<String> {
+        self.to_dfa().longest_match()
+    }
+
+    /// Check whether the [Graph] matches exactly the same strings as the other, or find the shortlex-least string
+    /// matched by only one of them.
+    pub fn is_equivalent(&self, other: &Self) -> Result<(), Counterexample> {
+        self.compare(other, |matched_by_self, matched_by_other| {
+            matched_by_self != matched_by_other
+        })
+    }
+
+    /// Check whether every string matched by the [Graph] is also matched by the other, or find the shortlex-least
+    /// string matched by this [Graph] but not the other.
+    pub fn is_subset_of(&self, other: &Self) -> Result<(), Counterexample> {
+        self.compare(other, |matched_by_self, matched_by_other| {
+            matched_by_self && !matched_by_other
+        })
+    }
+
+    /// Search both graphs for the shortlex-least string for which `is_counterexample` holds.
+    fn compare(
+        &self,
+        other: &Self,
+        is_counterexample: impl Fn(bool, bool) -> bool,
+    ) -> Result<(), Counterexample> {
+        // both graphs must be determinized over the same alphabet to be compared.
+        let alphabet = Alphabet::new(self.char_classes().into_iter().chain(other.char_classes()));
+
+        let dfa = Dfa::with_alphabet(self, alphabet.clone());
+        let other_dfa = Dfa::with_alphabet(other, alphabet);
+
+        match dfa.shortest_distinguishing(&other_dfa, is_counterexample) {
+            Some(string) => {
+                let matched_by_self = dfa.matches(&string);
+
+                Err(Counterexample::new(string, matched_by_self))
             }
+            None => Ok(()),
+        }
+    }
+
+    /// Produce every string of at most `max_len` characters matched by the [Graph], drawn from [Graph::alphabet].
+    ///
+    /// Broad classes such as `.` make for a very large alphabet - see [Graph::produce_strings_with] to restrict it.
+    pub fn produce_strings<'a>(&'a self, max_len: usize) -> ProduceStrings<'a> {
+        self.produce_strings_with(self.alphabet(), max_len)
+    }
+
+    /// Produce every string of at most `max_len` characters matched by the [Graph] in shortlex order, so the first
+    /// strings produced are the shortest examples of matches.
+    ///
+    /// See [Graph::produce_shortlex_with] to restrict the alphabet.
+    pub fn produce_shortlex<'a>(&'a self, max_len: usize) -> ProduceStrings<'a> {
+        self.produce_shortlex_with(self.alphabet(), max_len)
+    }
+
+    /// Produce every string of at most `max_len` characters matched by the [Graph] in shortlex order using only
+    /// characters from the alphabet.
+    pub fn produce_shortlex_with<'a>(
+        &'a self,
+        alphabet: CharClass,
+        max_len: usize,
+    ) -> ProduceStrings<'a> {
+        ProduceStrings::new(self, alphabet, max_len, Order::Shortlex)
+    }
+
+    /// Produce every string of at most `max_len` characters matched by the [Graph] using only characters from the
+    /// alphabet.
+    pub fn produce_strings_with<'a>(
+        &'a self,
+        alphabet: CharClass,
+        max_len: usize,
+    ) -> ProduceStrings<'a> {
+        ProduceStrings::new(self, alphabet, max_len, Order::DepthFirst)
+    }
+
+    /// Produce strings of at most `max_len` characters not matched by the [Graph], near misses - one edit away from a
+    /// match - first.
+    pub fn produce_non_matching<'a>(&'a self, max_len: usize) -> ProduceNonMatching<'a> {
+        ProduceNonMatching::new(self, max_len)
+    }
+
+    /// A small set of matching strings which between them traverse every [Rule] that can take part in a match.
+    ///
+    /// Unlike [Graph::produce_strings], this gives a handful of thorough examples rather than every string.
+    pub fn covering_strings(&self) -> Vec<String> {
+        self.covering_strings_with(Coverage::Rules)
+    }
+
+    /// A small set of matching strings which between them traverse everything required by the coverage.
+    pub fn covering_strings_with(&self, coverage: Coverage) -> Vec<String> {
+        cover::covering_strings(self, coverage)
+    }
+
+    /// The characters matched by any of the [Graph]'s rules, i.e. every character that can appear in a match.
+    pub fn alphabet(&self) -> CharClass {
+        self.char_classes()
+            .into_iter()
+            .fold(CharClass::empty(), |alphabet, class| alphabet.union(class))
+    }
+
+    /// The rules indexed by the state they start from.
+    fn rule_index(&self) -> &RuleIndex {
+        self.rule_index
+            .get_or_init(|| RuleIndex::new(self.state_count, &self.rules))
+    }
+
+    /// Get the epsilon rules starting at the state, in order of preference.
+    pub(crate) fn epsilon_rules_from(&self, state: State) -> impl Iterator<Item = &Rule> {
+        self.rule_index()
+            .epsilon_rules(state)
+            .iter()
+            .map(|&index| &self.rules[index])
+    }
+
+    /// Get the consuming rules starting at the state, in order of preference.
+    pub(crate) fn consuming_rules_from(&self, state: State) -> impl Iterator<Item = &Rule> {
+        self.rule_index()
+            .consuming_rules(state)
+            .iter()
+            .map(|&index| &self.rules[index])
+    }
+
+    /// Get the [CharClass] of every consuming rule.
+    pub(crate) fn char_classes(&self) -> Vec<&CharClass> {
+        self.rules.iter().filter_map(Rule::char_class).collect()
+    }
+
+    /// Add the states reachable by any number of epsilon rules (including zero) from any of the starting states to
+    /// the set.
+    ///
+    /// The set must only contain states added by [Graph::follow_epsilon_rules] or [Graph::follow_rules], e.g. by
+    /// being cleared first.
+    pub(crate) fn follow_epsilon_rules(
+        &self,
+        start_states: impl IntoIterator<Item = State>,
+        states: &mut StateSet,
+    ) {
+        for state in start_states {
+            self.rule_index()
+                .add_epsilon_closure(&self.rules, state, states);
         }
+    }
 
-        let starting_rules = self
+    /// Add the states reachable by matching the character from any of the starting states, followed by any number
+    /// of epsilon rules, to the set.
+    ///
+    /// The set must only contain states added by [Graph::follow_epsilon_rules] or [Graph::follow_rules], e.g. by
+    /// being cleared first.
+    pub(crate) fn follow_rules(
+        &self,
+        start_states: impl IntoIterator<Item = State>,
+        character: char,
+        states: &mut StateSet,
+    ) {
+        let end_states = start_states
+            .into_iter()
+            .flat_map(|state| self.consuming_rules_from(state))
+            // filter for rules that match the current character.
+            .filter(|rule| rule.matches(character))
+            .map(Rule::end);
+
+        for state in end_states {
+            self.rule_index()
+                .add_epsilon_closure(&self.rules, state, states);
+        }
+    }
+}
+
+impl Graph {
+    /// Renumber every state `offset` further along, so the [Graph]'s states can follow another's in a combined graph.
+    fn shifted(self, offset: usize) -> Self {
+        let rules = self
             .rules
             .iter()
-            // we only want epsilon rules.
-            .filter(|rule| rule.is_epsilon())
-            .collect();
+            .map(|rule| rule.with_states(rule.start().shifted(offset), rule.end().shifted(offset)));
 
-        follow_epsilons_impl(starting_rules, start_states)
-    }
-
-    fn follow_rules(&self, start_states: &HashSet<State>, character: char) -> HashSet<State> {
-        if start_states.is_empty() {
-            HashSet::new()
-        } else {
-            let end_states = self
-                .rules
-                .iter()
-                // filter for rules that match the current character.
-                .filter(|rule| rule.matches(character))
-                // filter for rules that start inside the set of start states.
-                .filter(|rule| start_states.contains(&rule.start()))
-                // get the set of states reachable from the end state of each rule
-                .map(|rule| rule.end())
-                .collect();
+        let groups = self.groups.into_iter().map(|group| Group {
+            boundaries: group
+                .boundaries
+                .into_iter()
+                .map(|(start, end)| (start.shifted(offset), end.shifted(offset)))
+                .collect(),
+            ..group
+        });
+
+        Self::new(
+            self.label,
+            self.state_count + offset,
+            self.start.shifted(offset),
+            self.end.shifted(offset),
+            rules,
+            groups,
+        )
+    }
+
+    /// Build a [Graph] with a state for every live state of the [Dfa], and a rule for every pair of states with a
+    /// transition between them.
+    fn from_dfa(label: String, dfa: &Dfa) -> Self {
+        // number each state as in the dfa, then add the single end state.
+        let states: Vec<_> = (0..dfa.state_count()).map(State::new).collect();
+
+        let start = states[dfa.start()];
+        let end = State::new(dfa.state_count());
 
-            self.follow_epsilon_rules(end_states)
+        let mut rules = Vec::new();
+
+        for state in (0..dfa.state_count()).filter(|&state| state != Dfa::DEAD) {
+            // merge every class leading to the same state into one rule.
+            let mut classes: Vec<(usize, CharClass)> = Vec::new();
+
+            for (class, char_class) in dfa.alphabet().classes().enumerate() {
+                let next = dfa.transition(state, class);
+
+                if next == Dfa::DEAD {
+                    continue;
+                }
+
+                match classes.iter_mut().find(|(existing, _)| *existing == next) {
+                    Some((_, merged)) => *merged = merged.union(char_class),
+                    None => classes.push((next, char_class.clone())),
+                }
+            }
+
+            rules.extend(
+                classes
+                    .into_iter()
+                    .map(|(next, class)| Rule::class(states[state], states[next], class)),
+            );
+
+            if dfa.is_accepting(state) {
+                // add path to the single end state.
+                rules.push(Rule::epsilon(states[state], end));
+            }
         }
+
+        Self::new(label, dfa.state_count() + 1, start, end, rules, [])
     }
 }
 
 impl From<char> for Graph {
     fn from(character: char) -> Self {
-        let start = State::new();
-        let end = State::new();
+        let (start, end) = (State::new(0), State::new(1));
 
         Self::new(
             character.to_string(),
+            2,
             start,
             end,
             [
-                // add lambda rule matching the character.
+                // add rule matching the character.
                 Rule::match_eq(start, end, character),
             ],
+            [],
         )
     }
 }
 
+impl FromStr for Graph {
+    type Err = ParseError;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        Self::parse(pattern)
+    }
+}
+
 impl Debug for Graph {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        write!(f, "digraph \"{}\" {{\n", self.label)?;
+        writeln!(f, "digraph \"{}\" {{", self.label)?;
 
-        write!(f, "{:?} [shape=square];\n", self.start)?;
-        write!(f, "{:?} [shape=doublecircle];\n", self.end)?;
+        writeln!(f, "{:?} [shape=square];", self.start)?;
+        writeln!(f, "{:?} [shape=doublecircle];", self.end)?;
 
         for rule in &self.rules {
-            write!(f, "{:?};\n", rule)?;
+            writeln!(f, "{:?};", rule)?;
         }
 
         write!(f, "}}")?;
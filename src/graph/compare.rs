@@ -0,0 +1,46 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// A string showing that two [Graph](super::Graph)s don't match the same language: it is matched by exactly one of
+/// them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Counterexample {
+    string: String,
+    matched_by_self: bool,
+}
+
+impl Counterexample {
+    pub(super) fn new(string: String, matched_by_self: bool) -> Self {
+        Self {
+            string,
+            matched_by_self,
+        }
+    }
+
+    /// The counterexample, which is shortlex-least (shortest, then least character by character) among all
+    /// counterexamples.
+    pub fn string(&self) -> &str {
+        self.string.as_str()
+    }
+
+    /// Whether the string is matched by the [Graph](super::Graph) the comparison was called on, rather than the
+    /// other one.
+    pub fn matched_by_self(&self) -> bool {
+        self.matched_by_self
+    }
+}
+
+impl Display for Counterexample {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let (matched, unmatched) = if self.matched_by_self {
+            ("self", "other")
+        } else {
+            ("other", "self")
+        };
+
+        write!(
+            f,
+            "{:?} is matched by {matched} but not by {unmatched}",
+            self.string
+        )
+    }
+}
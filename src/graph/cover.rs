@@ -0,0 +1,209 @@
+use super::{Graph, Rule, State};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// What the strings from [Graph::covering_strings_with] must traverse between them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Coverage {
+    /// Every [Rule] that can take part in a match.
+    #[default]
+    Rules,
+
+    /// Every pair of consecutive [Rule]s - one ending where the other starts - that can take part in a match, as well
+    /// as every [Rule].
+    RulePairs,
+}
+
+/// Shortest paths through a [Graph], counting only the consuming rules, as indices into its rules.
+struct ShortestPaths<'g> {
+    graph: &'g Graph,
+
+    /// The last rule on the shortest path from the start to each reachable state, or [None] for the start itself.
+    from_start: HashMap<State, Option<usize>>,
+
+    /// The first rule on the shortest path from each state to the end, or [None] for the end itself.
+    to_end: HashMap<State, Option<usize>>,
+}
+
+impl<'g> ShortestPaths<'g> {
+    fn new(graph: &'g Graph) -> Self {
+        let mut rules_from: HashMap<State, Vec<usize>> = HashMap::new();
+        let mut rules_to: HashMap<State, Vec<usize>> = HashMap::new();
+
+        for (index, rule) in graph.rules.iter().enumerate() {
+            if is_traversable(rule) {
+                rules_from.entry(rule.start()).or_default().push(index);
+                rules_to.entry(rule.end()).or_default().push(index);
+            }
+        }
+
+        let from_start = shortest_path_tree(graph, graph.start, |state| {
+            rules_from
+                .get(&state)
+                .into_iter()
+                .flatten()
+                .map(|&index| (index, graph.rules[index].end()))
+                .collect()
+        });
+
+        // walk the rules backwards from the end.
+        let to_end = shortest_path_tree(graph, graph.end, |state| {
+            rules_to
+                .get(&state)
+                .into_iter()
+                .flatten()
+                .map(|&index| (index, graph.rules[index].start()))
+                .collect()
+        });
+
+        Self {
+            graph,
+            from_start,
+            to_end,
+        }
+    }
+
+    /// Query whether the rules can take part in a match, in sequence.
+    fn is_useful(&self, rules: &[usize]) -> bool {
+        let (Some(&first), Some(&last)) = (rules.first(), rules.last()) else {
+            return false;
+        };
+
+        rules
+            .iter()
+            .all(|&index| is_traversable(&self.graph.rules[index]))
+            && self
+                .from_start
+                .contains_key(&self.graph.rules[first].start())
+            && self.to_end.contains_key(&self.graph.rules[last].end())
+    }
+
+    /// The shortest path from the start to the end through the rules, in sequence.
+    fn path_through(&self, rules: &[usize]) -> Vec<usize> {
+        let (Some(&first), Some(&last)) = (rules.first(), rules.last()) else {
+            return Vec::new();
+        };
+
+        let mut path = Vec::new();
+
+        let mut state = self.graph.rules[first].start();
+
+        while let Some(&Some(index)) = self.from_start.get(&state) {
+            path.push(index);
+            state = self.graph.rules[index].start();
+        }
+
+        path.reverse();
+        path.extend(rules);
+
+        let mut state = self.graph.rules[last].end();
+
+        while let Some(&Some(index)) = self.to_end.get(&state) {
+            path.push(index);
+            state = self.graph.rules[index].end();
+        }
+
+        path
+    }
+}
+
+/// Find the shortest path from the root to every state reachable by the edges, as the last rule on each path.
+///
+/// Epsilon rules cost nothing, so this is a breadth-first search which explores them before consuming rules.
+fn shortest_path_tree(
+    graph: &Graph,
+    root: State,
+    edges: impl Fn(State) -> Vec<(usize, State)>,
+) -> HashMap<State, Option<usize>> {
+    let mut distances = HashMap::from([(root, 0)]);
+    let mut tree = HashMap::from([(root, None)]);
+    let mut queue = VecDeque::from([(root, 0)]);
+
+    while let Some((state, distance)) = queue.pop_front() {
+        // skip states since reached by a shorter path.
+        if distances[&state] < distance {
+            continue;
+        }
+
+        for (index, next) in edges(state) {
+            let is_epsilon = graph.rules[index].is_epsilon();
+            let next_distance = distance + usize::from(!is_epsilon);
+
+            if distances
+                .get(&next)
+                .is_some_and(|&distance| distance <= next_distance)
+            {
+                continue;
+            }
+
+            distances.insert(next, next_distance);
+            tree.insert(next, Some(index));
+
+            if is_epsilon {
+                queue.push_front((next, next_distance));
+            } else {
+                queue.push_back((next, next_distance));
+            }
+        }
+    }
+
+    tree
+}
+
+/// Query whether any string can traverse the rule.
+fn is_traversable(rule: &Rule) -> bool {
+    rule.char_class().is_none_or(|class| !class.is_empty())
+}
+
+/// Find matching strings which between them traverse everything required by the coverage.
+///
+/// Each string is the shortest one through something not yet traversed, so the strings are short but not
+/// necessarily the fewest possible.
+pub(crate) fn covering_strings(graph: &Graph, coverage: Coverage) -> Vec<String> {
+    let paths = ShortestPaths::new(graph);
+
+    let rules = (0..graph.rules.len()).map(|index| vec![index]);
+
+    let targets: Vec<Vec<usize>> = match coverage {
+        Coverage::Rules => rules.collect(),
+        // a rule alone on a path from the start to the end is in no pair, so cover the rules as well.
+        Coverage::RulePairs => (0..graph.rules.len())
+            .flat_map(|first| {
+                (0..graph.rules.len())
+                    .filter(move |&second| graph.rules[first].end() == graph.rules[second].start())
+                    .map(move |second| vec![first, second])
+            })
+            .chain(rules)
+            .collect(),
+    };
+
+    let mut covered = HashSet::new();
+    let mut strings = Vec::new();
+
+    for target in targets {
+        if covered.contains(&target) || !paths.is_useful(&target) {
+            continue;
+        }
+
+        let path = paths.path_through(&target);
+
+        // the path traverses its own rules and pairs as well as the target.
+        covered.extend(path.iter().map(|&index| vec![index]));
+        covered.extend(path.windows(2).map(<[usize]>::to_vec));
+
+        let string: String = path
+            .iter()
+            .filter_map(|&index| graph.rules[index].char_class())
+            .map(|class| {
+                class
+                    .representative()
+                    .expect("traversable classes should not be empty")
+            })
+            .collect();
+
+        if !strings.contains(&string) {
+            strings.push(string);
+        }
+    }
+
+    strings
+}
@@ -1,16 +1,29 @@
 use super::{Graph, State};
-use std::collections::HashSet;
+use crate::state::StateSet;
+use std::mem;
 
 pub struct Evaluate<'a> {
     graph: &'a Graph,
-    current_states: HashSet<State>,
+    current_states: StateSet,
+
+    /// Spare space for the states after the next character, kept so following rules doesn't allocate.
+    next_states: StateSet,
 }
 
 impl<'a> Evaluate<'a> {
     pub fn new(graph: &'a Graph) -> Self {
+        Self::from_states(graph, [graph.start])
+    }
+
+    /// Resume evaluation from the epsilon closure of a set of states, e.g. one handed over by a lazy DFA.
+    pub(crate) fn from_states(graph: &'a Graph, states: impl IntoIterator<Item = State>) -> Self {
+        let mut current_states = StateSet::new(graph.state_count());
+        graph.follow_epsilon_rules(states, &mut current_states);
+
         Self {
             graph,
-            current_states: graph.follow_epsilon_rules([graph.start].into()),
+            current_states,
+            next_states: StateSet::new(graph.state_count()),
         }
     }
 
@@ -19,23 +32,25 @@ impl<'a> Evaluate<'a> {
     }
 
     pub fn is_in_end_state(&self) -> bool {
-        self.current_states.contains(&self.graph.end)
+        self.current_states.contains(self.graph.end)
     }
 
-    pub fn current_states<'b>(&'b self) -> &'b HashSet<State> {
-        &self.current_states
+    /// Iterate over the current states, in no particular order.
+    pub fn current_states(&self) -> impl Iterator<Item = State> + '_ {
+        self.current_states.iter()
     }
 
-    pub fn try_follow_rules(self, character: char) -> Option<Self> {
-        let next = self.graph.follow_rules(&self.current_states, character);
+    pub fn try_follow_rules(mut self, character: char) -> Option<Self> {
+        self.next_states.clear();
+        self.graph
+            .follow_rules(self.current_states.iter(), character, &mut self.next_states);
 
-        if next.is_empty() {
+        if self.next_states.is_empty() {
             None
         } else {
-            Some(Self {
-                graph: self.graph,
-                current_states: next,
-            })
+            mem::swap(&mut self.current_states, &mut self.next_states);
+
+            Some(self)
         }
     }
 }
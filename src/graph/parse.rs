@@ -0,0 +1,465 @@
+use super::Graph;
+use crate::char_class::CharClass;
+use std::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+    iter::Peekable,
+    ops::Range,
+    str::CharIndices,
+};
+
+/// An error produced when a pattern string cannot be parsed into a [Graph].
+///
+/// The [Display] implementation renders the pattern with a caret under the offending span, e.g.
+///
+/// ```text
+/// error: unclosed group
+///   |
+///   | a(b|
+///   |  ^
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    span: Range<usize>,
+    pattern: String,
+}
+
+impl ParseError {
+    fn new(kind: ParseErrorKind, span: Range<usize>, pattern: &str) -> Self {
+        Self {
+            kind,
+            span,
+            pattern: pattern.to_owned(),
+        }
+    }
+
+    /// What went wrong.
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// The byte range of the offending part of the pattern.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// The pattern that failed to parse.
+    pub fn pattern(&self) -> &str {
+        self.pattern.as_str()
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        // count characters rather than bytes so the caret lines up with multi-byte characters.
+        let offset = self.pattern[..self.span.start].chars().count();
+        let width = self.pattern[self.span.clone()].chars().count().max(1);
+
+        writeln!(f, "error: {}", self.kind)?;
+        writeln!(f, "  |")?;
+        writeln!(f, "  | {}", self.pattern)?;
+        write!(f, "  | {}{}", " ".repeat(offset), "^".repeat(width))
+    }
+}
+
+/// The kinds of [ParseError].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A `(` was never closed by a matching `)`.
+    UnclosedGroup,
+
+    /// A `)` was found without a matching `(`.
+    UnopenedGroup,
+
+    /// A quantifier (`*`, `+`, `?` or `{n,m}`) was found with nothing to repeat.
+    MissingRepeatOperand,
+
+    /// A `{` was not followed by valid repetition bounds (`{n}`, `{n,}` or `{n,m}` with `n <= m`).
+    InvalidRepetition,
+
+    /// A `\` was followed by a character that has no escape meaning.
+    InvalidEscape(char),
+
+    /// The pattern ended with an unescaped `\`.
+    TrailingBackslash,
+
+    /// A `[` was never closed by a matching `]`.
+    UnclosedClass,
+
+    /// A character class matches no characters, e.g. `[]`.
+    EmptyClass,
+
+    /// A group name is empty, unterminated, or contains characters other than letters, digits and `_`.
+    InvalidGroupName,
+
+    /// A range in a character class is out of order (e.g. `z-a`) or has a class as an endpoint (e.g. `a-\d` or
+    /// `\d-z`).
+    InvalidClassRange,
+
+    /// A repetition would build a [Graph] with more than [MAX_REPETITION_STATES] states, e.g. `a{1000000}`.
+    RepetitionTooLarge,
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::UnclosedGroup => write!(f, "unclosed group"),
+            Self::UnopenedGroup => write!(f, "unopened group"),
+            Self::MissingRepeatOperand => write!(f, "quantifier has nothing to repeat"),
+            Self::InvalidRepetition => write!(f, "invalid repetition bounds"),
+            Self::InvalidEscape(character) => write!(f, "invalid escape sequence \\{character}"),
+            Self::TrailingBackslash => write!(f, "pattern ends with a trailing backslash"),
+            Self::UnclosedClass => write!(f, "unclosed character class"),
+            Self::EmptyClass => write!(f, "character class matches no characters"),
+            Self::InvalidClassRange => write!(f, "invalid character class range"),
+            Self::InvalidGroupName => write!(f, "invalid capture group name"),
+            Self::RepetitionTooLarge => write!(f, "repetition is too large"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// The most states a repetition may build, so a large bound is rejected rather than exhausting memory.
+pub const MAX_REPETITION_STATES: usize = 100_000;
+
+/// Recursive descent parser turning a pattern string into a [Graph] using the [Graph] combinators.
+///
+/// The grammar, from lowest to highest precedence, is:
+///
+/// ```text
+/// alternation := concatenation ('|' concatenation)*
+/// concatenation := repetition*
+/// repetition := atom ('*' | '+' | '?' | '{' number (',' number?)? '}')*
+/// atom := literal | '.' | '\' escape | '(' group_flags alternation ')' | '[' '^'? class_item* ']'
+/// group_flags := ('?:' | '?<' name '>' | '?P<' name '>')?
+/// class_item := class_char ('-' class_char)? | '\' class_escape
+/// ```
+pub(super) struct Parser<'a> {
+    pattern: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(pattern: &'a str) -> Self {
+        Self {
+            pattern,
+            chars: pattern.char_indices().peekable(),
+        }
+    }
+
+    /// Create an error spanning `len` bytes from `start`.
+    fn error(&self, kind: ParseErrorKind, start: usize, len: usize) -> ParseError {
+        ParseError::new(kind, start..start + len, self.pattern)
+    }
+
+    /// Parse the whole pattern.
+    pub fn parse(mut self) -> Result<Graph, ParseError> {
+        let graph = self.parse_alternation()?;
+
+        // the only way to stop parsing an alternation early is an unmatched `)`.
+        match self.chars.next() {
+            Some((index, _)) => Err(self.error(ParseErrorKind::UnopenedGroup, index, 1)),
+            None => Ok(graph),
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, character)| character)
+    }
+
+    /// The byte offset of the next character, or the end of the pattern.
+    fn position(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map_or(self.pattern.len(), |&(index, _)| index)
+    }
+
+    fn parse_alternation(&mut self) -> Result<Graph, ParseError> {
+        let mut graph = self.parse_concatenation()?;
+
+        while self.peek() == Some('|') {
+            self.chars.next();
+
+            graph = graph.union(self.parse_concatenation()?);
+        }
+
+        Ok(graph)
+    }
+
+    fn parse_concatenation(&mut self) -> Result<Graph, ParseError> {
+        let mut graph: Option<Graph> = None;
+
+        // a concatenation ends at the end of its alternative or its group.
+        while !matches!(self.peek(), None | Some('|' | ')')) {
+            let next = self.parse_repetition()?;
+
+            graph = Some(match graph {
+                Some(graph) => graph.concat(next),
+                None => next,
+            });
+        }
+
+        Ok(graph.unwrap_or_else(Graph::empty))
+    }
+
+    fn parse_repetition(&mut self) -> Result<Graph, ParseError> {
+        let mut graph = self.parse_atom()?;
+
+        loop {
+            graph = match self.peek() {
+                Some('*') => graph.zero_or_more(),
+                Some('+') => graph.one_or_more(),
+                Some('?') => graph.optional(),
+                Some('{') => {
+                    let (min, max) = self.parse_bounds(graph.state_count())?;
+
+                    graph.repeat(min, max)
+                }
+                _ => return Ok(graph),
+            };
+
+            self.chars.next();
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<Graph, ParseError> {
+        // `parse_concatenation` only calls this when there is a character to consume.
+        let (index, character) = self.chars.next().expect("atom should not be empty");
+
+        match character {
+            '.' => Ok(Graph::any()),
+            '*' | '+' | '?' | '{' => {
+                Err(self.error(ParseErrorKind::MissingRepeatOperand, index, 1))
+            }
+            '\\' => self.parse_escape(index).map(|escape| match escape {
+                Escape::Char(character) => Graph::from(character),
+                Escape::Class(class) => Graph::class(class),
+            }),
+            '[' => self.parse_class(index).map(Graph::class),
+            '(' => {
+                let group = self.parse_group_flags(index)?;
+                let graph = self.parse_alternation()?;
+
+                match self.chars.next() {
+                    Some((_, ')')) => Ok(match group {
+                        Some(name) => graph.group(name),
+                        None => graph,
+                    }),
+                    // point at the `(` that was left open.
+                    _ => Err(self.error(ParseErrorKind::UnclosedGroup, index, 1)),
+                }
+            }
+            character => Ok(Graph::from(character)),
+        }
+    }
+
+    /// Parse the bounds of a repetition, `{n}`, `{n,}` or `{n,m}`, leaving the closing `}` to be consumed by the
+    /// caller like any other quantifier.
+    ///
+    /// The bounds are rejected if repeating an operand with `operand_state_count` states would build more than
+    /// [MAX_REPETITION_STATES] states.
+    fn parse_bounds(
+        &mut self,
+        operand_state_count: usize,
+    ) -> Result<(usize, Option<usize>), ParseError> {
+        let (index, _) = self.chars.next().expect("bounds should start with `{`");
+
+        let min = self.parse_number();
+
+        let max = if self
+            .chars
+            .next_if(|&(_, character)| character == ',')
+            .is_some()
+        {
+            // a missing maximum means the repetition is unbounded.
+            if self.peek() == Some('}') {
+                Some(None)
+            } else {
+                self.parse_number().map(Some)
+            }
+        } else {
+            min.map(Some)
+        };
+
+        // point at everything up to and including the closing `}`, if there is one.
+        let end = self.position() + usize::from(self.peek() == Some('}'));
+
+        match (min, max, self.peek()) {
+            (Some(min), Some(max), Some('}')) if max.is_none_or(|max| min <= max) => {
+                // each copy needs the operand's states plus one to link it, and an unbounded repetition one more copy.
+                let state_count = max
+                    .unwrap_or(min)
+                    .checked_add(1)
+                    .and_then(|copy_count| copy_count.checked_mul(operand_state_count + 1));
+
+                if state_count.is_none_or(|state_count| state_count > MAX_REPETITION_STATES) {
+                    return Err(self.error(ParseErrorKind::RepetitionTooLarge, index, end - index));
+                }
+
+                Ok((min, max))
+            }
+            _ => Err(self.error(ParseErrorKind::InvalidRepetition, index, end - index)),
+        }
+    }
+
+    /// Parse a decimal number, or [None] if there are no digits or the number is too large.
+    fn parse_number(&mut self) -> Option<usize> {
+        let start = self.position();
+
+        while self
+            .chars
+            .next_if(|(_, character)| character.is_ascii_digit())
+            .is_some()
+        {}
+
+        self.pattern[start..self.position()].parse().ok()
+    }
+
+    /// Parse what kind of group the `(` at `index` opens: [None] for a non-capturing group (`(?:`), otherwise the
+    /// name of the capture group, if it has one (`(?<name>` or `(?P<name>`).
+    fn parse_group_flags(&mut self, index: usize) -> Result<Option<Option<&'a str>>, ParseError> {
+        // anything else starting `(?` is left for `parse_atom` to report as a quantifier with nothing to repeat.
+        let mut lookahead = self.chars.clone();
+
+        let prefix_len = match (lookahead.next(), lookahead.next(), lookahead.next()) {
+            (Some((_, '?')), Some((_, ':')), _) => {
+                self.chars.nth(1);
+                return Ok(None);
+            }
+            (Some((_, '?')), Some((_, '<')), _) => 2,
+            (Some((_, '?')), Some((_, 'P')), Some((_, '<'))) => 3,
+            _ => return Ok(Some(None)),
+        };
+
+        self.chars.nth(prefix_len - 1);
+
+        let name_start = self.position();
+
+        // the name runs up to the closing `>`.
+        while self
+            .chars
+            .next_if(|&(_, character)| character != '>')
+            .is_some()
+        {}
+
+        let name_end = self.position();
+        let name = &self.pattern[name_start..name_end];
+
+        let is_valid = self.chars.next().is_some()
+            && name.starts_with(|character: char| character.is_alphabetic() || character == '_')
+            && name
+                .chars()
+                .all(|character| character.is_alphanumeric() || character == '_');
+
+        if is_valid {
+            Ok(Some(Some(name)))
+        } else {
+            let len = self.position() - index;
+
+            Err(self.error(ParseErrorKind::InvalidGroupName, index, len))
+        }
+    }
+
+    /// Parse a character class, the `[` of which is at `index`.
+    fn parse_class(&mut self, index: usize) -> Result<CharClass, ParseError> {
+        let negated = self
+            .chars
+            .next_if(|&(_, character)| character == '^')
+            .is_some();
+
+        let mut ranges = Vec::new();
+
+        loop {
+            let Some((item_index, character)) = self.chars.next() else {
+                return Err(self.error(ParseErrorKind::UnclosedClass, index, 1));
+            };
+
+            let start = match character {
+                ']' => break,
+                '\\' => self.parse_escape(item_index)?,
+                character => Escape::Char(character),
+            };
+
+            // a `-` forms a range unless it is the last character in the class.
+            let mut lookahead = self.chars.clone();
+            let is_range = matches!(lookahead.next(), Some((_, '-')))
+                && !matches!(lookahead.next(), None | Some((_, ']')));
+
+            if !is_range {
+                match start {
+                    Escape::Char(character) => ranges.push(character..=character),
+                    Escape::Class(class) => ranges.extend(class.ranges()),
+                }
+
+                continue;
+            }
+
+            // skip the `-`.
+            self.chars.next();
+
+            let end = match self.chars.next() {
+                Some((end_index, '\\')) => match self.parse_escape(end_index)? {
+                    Escape::Char(character) => Some(character),
+                    Escape::Class(_) => None,
+                },
+                Some((_, character)) => Some(character),
+                None => unreachable!("lookahead should have found the end of the range"),
+            };
+
+            match (start, end) {
+                (Escape::Char(start), Some(end)) if start <= end => ranges.push(start..=end),
+                _ => {
+                    let len = self.position() - item_index;
+
+                    return Err(self.error(ParseErrorKind::InvalidClassRange, item_index, len));
+                }
+            }
+        }
+
+        let class = CharClass::new(ranges);
+        let class = if negated { class.negate() } else { class };
+
+        if class.is_empty() {
+            let len = self.position() - index;
+
+            Err(self.error(ParseErrorKind::EmptyClass, index, len))
+        } else {
+            Ok(class)
+        }
+    }
+
+    /// Parse the character following the `\` at `index`.
+    fn parse_escape(&mut self, index: usize) -> Result<Escape, ParseError> {
+        let Some((_, character)) = self.chars.next() else {
+            return Err(self.error(ParseErrorKind::TrailingBackslash, index, 1));
+        };
+
+        match character {
+            'n' => Ok(Escape::Char('\n')),
+            'r' => Ok(Escape::Char('\r')),
+            't' => Ok(Escape::Char('\t')),
+            '0' => Ok(Escape::Char('\0')),
+            'd' => Ok(Escape::Class(CharClass::digit())),
+            'D' => Ok(Escape::Class(CharClass::digit().negate())),
+            'w' => Ok(Escape::Class(CharClass::word())),
+            'W' => Ok(Escape::Class(CharClass::word().negate())),
+            's' => Ok(Escape::Class(CharClass::whitespace())),
+            'S' => Ok(Escape::Class(CharClass::whitespace().negate())),
+            // any punctuation may be escaped to match itself.
+            character if character.is_ascii_punctuation() => Ok(Escape::Char(character)),
+            character => Err(self.error(
+                ParseErrorKind::InvalidEscape(character),
+                index,
+                1 + character.len_utf8(),
+            )),
+        }
+    }
+}
+
+/// The meaning of an escape sequence.
+enum Escape {
+    Char(char),
+    Class(CharClass),
+}
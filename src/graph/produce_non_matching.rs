@@ -0,0 +1,165 @@
+use super::{
+    produce_strings::{Order, ProduceStrings},
+    Graph,
+};
+use crate::dfa::Dfa;
+use std::collections::{HashSet, VecDeque};
+
+/// [Iterator] over strings not matched by a [Graph], near misses first.
+///
+/// First come the strings one edit - deleting, substituting or inserting a character - away from a match, in
+/// shortlex order of the match, then every other non-matching string in shortlex order.
+///
+/// Rather than every character, strings are made of one representative of each set of characters the [Graph] treats
+/// alike, e.g. `\d+` produces strings of `0` and `A`.
+pub struct ProduceNonMatching<'a> {
+    /// The [Graph] determinized, for quickly checking candidates.
+    dfa: Dfa,
+
+    /// The characters from which strings are produced.
+    characters: Vec<char>,
+
+    /// The maximum length of string we are producing.
+    max_len: usize,
+
+    /// The matches whose near misses are yet to be produced.
+    matches: ProduceStrings<'a>,
+
+    /// The near misses of the matches so far, yet to be produced.
+    near_misses: VecDeque<String>,
+
+    /// The near misses produced so far, to avoid producing them again.
+    produced: HashSet<String>,
+
+    /// Once the near misses are exhausted, the next string to consider as indices into `characters`, or [None] once
+    /// every string has been considered.
+    next_string: Option<Vec<usize>>,
+}
+
+impl<'a> ProduceNonMatching<'a> {
+    pub(crate) fn new(graph: &'a Graph, max_len: usize) -> Self {
+        let dfa = graph.to_dfa().minimize();
+
+        let characters: Vec<_> = dfa
+            .alphabet()
+            .classes()
+            .map(|class| {
+                class
+                    .representative()
+                    .expect("equivalence classes should not be empty")
+            })
+            .collect();
+
+        let matches = ProduceStrings::new(
+            graph,
+            characters.iter().copied().collect(),
+            max_len,
+            Order::Shortlex,
+        );
+
+        Self {
+            dfa,
+            characters,
+            max_len,
+            matches,
+            near_misses: VecDeque::new(),
+            produced: HashSet::new(),
+            next_string: Some(Vec::new()),
+        }
+    }
+
+    /// Every string within the maximum length one edit away from the string.
+    fn edits(&self, string: &str) -> Vec<String> {
+        let chars: Vec<_> = string.chars().collect();
+
+        let splice = |start: usize, end: usize, insert: Option<char>| -> String {
+            chars[..start]
+                .iter()
+                .copied()
+                .chain(insert)
+                .chain(chars[end..].iter().copied())
+                .collect()
+        };
+
+        let mut edits = Vec::new();
+
+        for (position, &current) in chars.iter().enumerate() {
+            edits.push(splice(position, position + 1, None));
+
+            for &character in &self.characters {
+                if character != current {
+                    edits.push(splice(position, position + 1, Some(character)));
+                }
+            }
+        }
+
+        if chars.len() < self.max_len {
+            for position in 0..=chars.len() {
+                for &character in &self.characters {
+                    edits.push(splice(position, position, Some(character)));
+                }
+            }
+        }
+
+        edits
+    }
+
+    /// The next string in shortlex order, matching or not.
+    fn next_string(&mut self) -> Option<String> {
+        let indices = self.next_string.as_mut()?;
+
+        let string = indices
+            .iter()
+            .map(|&index| self.characters[index])
+            .collect();
+
+        // advance like an odometer, adding a character once every position wraps around.
+        match indices
+            .iter()
+            .rposition(|&index| index + 1 < self.characters.len())
+        {
+            Some(position) => {
+                indices[position] += 1;
+                indices[position + 1..].fill(0);
+            }
+            None if indices.len() < self.max_len => *indices = vec![0; indices.len() + 1],
+            None => self.next_string = None,
+        }
+
+        Some(string)
+    }
+}
+
+impl<'a> Iterator for ProduceNonMatching<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(near_miss) = self.near_misses.pop_front() {
+                if self.produced.insert(near_miss.clone()) {
+                    return Some(near_miss);
+                }
+
+                continue;
+            }
+
+            if let Some(matched) = self.matches.next() {
+                let near_misses: Vec<_> = self
+                    .edits(&matched)
+                    .into_iter()
+                    .filter(|edit| !self.dfa.matches(edit))
+                    .collect();
+
+                self.near_misses.extend(near_misses);
+
+                continue;
+            }
+
+            let string = self.next_string()?;
+
+            if !self.dfa.matches(&string) && !self.produced.contains(&string) {
+                return Some(string);
+            }
+        }
+    }
+}
@@ -1,21 +1,48 @@
 mod meta_state;
 
-use crate::{graph::Graph, state::State};
+use crate::{char_class::CharClass, graph::Graph, state::StateSet};
 use meta_state::MetaState;
-use std::collections::HashSet;
+use std::mem;
 
-const ALPHABET_SIZE: usize = 26;
+/// The order in which [ProduceStrings] yields strings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Order {
+    /// Depth-first: each string is followed by its extensions, e.g. `a*b*` yields `""`, `"b"`, `"bb"`, ..., then
+    /// `"a"`.
+    #[default]
+    DepthFirst,
+
+    /// Shortlex: shorter strings first, then by character, so the first strings yielded are the shortest.
+    ///
+    /// Strings are produced one length at a time, re-walking the shorter prefixes for each length.
+    Shortlex,
+}
 
 /// [Iterator] over all the valid strings for a [Graph].
 pub struct ProduceStrings<'a> {
     /// A reference to the graph for which we are producing strings.
     graph: &'a Graph,
 
+    /// The characters from which strings are produced.
+    alphabet: CharClass,
+
     /// The maximum length of string we are producing.
     max_len: usize,
 
+    order: Order,
+
+    /// In [Order::Shortlex], the length of string currently being produced.
+    target_len: usize,
+
+    /// In [Order::Shortlex], whether any prefix of length `target_len` has been reached - if not, there are no
+    /// longer strings either.
+    reached_target_len: bool,
+
     /// The set of states corresponding to producing the current string, according to the referenced graph.
-    current_states: HashSet<State>,
+    current_states: StateSet,
+
+    /// Sets of states no longer in use, kept so stepping between strings doesn't allocate.
+    spare_states: Vec<StateSet>,
 
     /// The sequence of steps (according to the referenced graph) taken to get to the current state.
     ///
@@ -24,13 +51,23 @@ pub struct ProduceStrings<'a> {
 }
 
 impl<'a> ProduceStrings<'a> {
-    pub fn new(graph: &'a Graph, max_len: usize) -> Self {
+    pub fn new(graph: &'a Graph, alphabet: CharClass, max_len: usize, order: Order) -> Self {
+        // the initial states includes epsilon transitions.
+        let mut current_states = StateSet::new(graph.state_count());
+        graph.follow_epsilon_rules([graph.start], &mut current_states);
+
         Self {
             graph,
+            alphabet,
             max_len,
+            order,
+
+            // the empty prefix is always reached.
+            target_len: 0,
+            reached_target_len: true,
 
-            // the initial states includes epsilon transitions.
-            current_states: graph.follow_epsilon_rules([graph.start].into()),
+            current_states,
+            spare_states: Vec::new(),
 
             // we know we will need at most `max_len` meta-states.
             meta_state_stack: Vec::with_capacity(max_len),
@@ -39,7 +76,23 @@ impl<'a> ProduceStrings<'a> {
 
     /// Query whether the string corresponding to the current state matches the regex graph.
     fn is_in_matching_state(&self) -> bool {
-        self.current_states.contains(&self.graph.end)
+        self.current_states.contains(self.graph.end)
+    }
+
+    /// Query whether the current string is of a length to be yielded.
+    fn is_at_target_len(&self) -> bool {
+        match self.order {
+            Order::DepthFirst => true,
+            Order::Shortlex => self.meta_state_stack.len() == self.target_len,
+        }
+    }
+
+    /// The length beyond which the current pass doesn't extend strings.
+    fn depth_limit(&self) -> usize {
+        match self.order {
+            Order::DepthFirst => self.max_len,
+            Order::Shortlex => self.target_len,
+        }
     }
 
     /// Query whether
@@ -47,11 +100,35 @@ impl<'a> ProduceStrings<'a> {
         self.current_states.is_empty() && self.meta_state_stack.is_empty()
     }
 
+    /// In [Order::Shortlex], start a new pass producing strings one character longer, returning whether there could
+    /// be any.
+    fn next_target_len(&mut self) -> bool {
+        if self.order != Order::Shortlex
+            || self.target_len >= self.max_len
+            || !self.reached_target_len
+        {
+            return false;
+        }
+
+        self.target_len += 1;
+        self.reached_target_len = false;
+        self.current_states.clear();
+        self.graph
+            .follow_epsilon_rules([self.graph.start], &mut self.current_states);
+
+        true
+    }
+
     fn next_impl(&mut self) {
         // create a new meta-state if we're not already at the max length.
-        let mut new_meta_state = if self.meta_state_stack.len() < self.max_len {
-            let states = self.current_states.drain().collect();
-            Some(MetaState::new(self.graph, states))
+        let mut new_meta_state = if self.meta_state_stack.len() < self.depth_limit() {
+            let spare_states = self
+                .spare_states
+                .pop()
+                .unwrap_or_else(|| StateSet::new(self.graph.state_count()));
+
+            let states = mem::replace(&mut self.current_states, spare_states);
+            Some(MetaState::new(self.graph, &self.alphabet, states))
         } else {
             None
         };
@@ -77,29 +154,31 @@ impl<'a> ProduceStrings<'a> {
             };
 
             // advance the current meta-state, looking for a non-empty set of new states.
-            while let Some((current_character, new_states)) = current_meta_state.next() {
+            while let Some(current_character) =
+                current_meta_state.next_into(&self.alphabet, &mut self.current_states)
+            {
                 // if the new set of states is not empty, we found a valid (though not necessarily matching) state.
-                if !new_states.is_empty() {
-                    self.current_states = new_states;
-
+                if !self.current_states.is_empty() {
                     self.meta_state_stack
                         .push((current_character, current_meta_state));
 
+                    self.reached_target_len |= self.meta_state_stack.len() == self.target_len;
+
                     return;
                 }
             }
 
             // the current meta-state is finished - backtrack and continue to the next loop iteration.
 
-            // record the current meta-state's set of states as the new current states.
-            self.current_states = current_meta_state.into_states();
+            // keep the current meta-state's set of states for reuse.
+            self.spare_states.push(current_meta_state.into_states());
         }
     }
 
-    fn size_hint_upper(max_len: usize) -> Option<usize> {
+    fn size_hint_upper(alphabet_size: usize, max_len: usize) -> Option<usize> {
         (0..max_len)
             .try_fold((1, 1), |(sum, alphabet_size_pow_n), _| {
-                let alphabet_size_pow_n = ALPHABET_SIZE.checked_mul(alphabet_size_pow_n)?;
+                let alphabet_size_pow_n = alphabet_size.checked_mul(alphabet_size_pow_n)?;
 
                 let sum = alphabet_size_pow_n.checked_add(sum)?;
 
@@ -113,9 +192,13 @@ impl<'a> Iterator for ProduceStrings<'a> {
     type Item = String;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while !self.is_in_matching_state() {
-            // check for end of iteration.
+        while !(self.is_in_matching_state() && self.is_at_target_len()) {
+            // check for end of iteration, or of the current pass in shortlex order.
             if self.is_finished() {
+                if self.next_target_len() {
+                    continue;
+                }
+
                 return None;
             }
 
@@ -134,33 +217,36 @@ impl<'a> Iterator for ProduceStrings<'a> {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (0, Self::size_hint_upper(self.max_len))
+        (0, Self::size_hint_upper(self.alphabet.len(), self.max_len))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::graph::produce_strings::{ProduceStrings, ALPHABET_SIZE};
+    use crate::graph::produce_strings::ProduceStrings;
 
-    fn size_hint_upper_recursive(max_len: usize) -> Option<usize> {
+    fn size_hint_upper_recursive(alphabet_size: usize, max_len: usize) -> Option<usize> {
         if max_len == 0 {
             Some(1)
         } else {
-            size_hint_upper_recursive(max_len - 1)?
-                .checked_add(ALPHABET_SIZE.checked_pow(max_len.try_into().ok()?)?)
+            size_hint_upper_recursive(alphabet_size, max_len - 1)?
+                .checked_add(alphabet_size.checked_pow(max_len.try_into().ok()?)?)
         }
     }
 
     #[test]
     fn test_size_hint() {
-        for i in 0.. {
-            let actual = ProduceStrings::size_hint_upper(i);
-            let expected = size_hint_upper_recursive(i);
+        for alphabet_size in [0, 1, 2, 26, 62, 1_112_064] {
+            for i in 0.. {
+                let actual = ProduceStrings::size_hint_upper(alphabet_size, i);
+                let expected = size_hint_upper_recursive(alphabet_size, i);
 
-            assert_eq!(actual, expected);
+                assert_eq!(actual, expected);
 
-            if expected.is_none() {
-                break;
+                // with fewer than two characters, the hint never overflows.
+                if expected.is_none() || i > 100 {
+                    break;
+                }
             }
         }
     }
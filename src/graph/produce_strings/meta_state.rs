@@ -1,37 +1,42 @@
-use super::{Graph, State};
-use std::{collections::HashSet, ops::RangeInclusive};
+use super::Graph;
+use crate::{char_class::CharClass, state::StateSet};
 
-/// [Iterator] over the [State]s reachable from a set of initial states by matching a single character.
+/// The [State](crate::state::State)s reachable from a set of initial states by matching each character of an
+/// alphabet in turn.
 pub(super) struct MetaState<'a> {
     graph: &'a Graph,
-    characters: RangeInclusive<char>,
-    states: HashSet<State>,
+
+    /// The next character to follow, or [None] once every character has been followed.
+    next_character: Option<char>,
+
+    states: StateSet,
 }
 
 impl<'a> MetaState<'a> {
-    pub fn new(graph: &'a Graph, states: HashSet<State>) -> Self {
+    pub fn new(graph: &'a Graph, alphabet: &CharClass, states: StateSet) -> Self {
         Self {
             graph,
-            characters: 'a'..='z',
+            next_character: alphabet.chars().next(),
             states,
         }
     }
 
-    pub fn into_states(self) -> HashSet<State> {
+    pub fn into_states(self) -> StateSet {
         self.states
     }
 
-    fn follow_rules(&self, character: char) -> HashSet<State> {
-        self.graph.follow_rules(&self.states, character)
-    }
-}
+    /// Replace the contents of `next_states` with the states reachable by the next character of the alphabet,
+    /// returning the character, or [None] once every character has been followed.
+    ///
+    /// The alphabet must be the one the meta-state was created with.
+    pub fn next_into(&mut self, alphabet: &CharClass, next_states: &mut StateSet) -> Option<char> {
+        let character = self.next_character?;
+        self.next_character = alphabet.next_after(character);
 
-impl<'a> Iterator for MetaState<'a> {
-    type Item = (char, HashSet<State>);
+        next_states.clear();
+        self.graph
+            .follow_rules(self.states.iter(), character, next_states);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.characters
-            .next()
-            .map(|character| (character, self.follow_rules(character)))
+        Some(character)
     }
 }
@@ -0,0 +1,84 @@
+use super::search::Captures;
+
+/// Something that can produce the replacement for a match, given its capture groups.
+///
+/// Implemented for `&str` and [String] as templates (see [Captures::expand]), and for closures returning the
+/// replacement text.
+pub trait Replacer {
+    /// Append the replacement for the match to `destination`.
+    fn replace_append(&mut self, captures: &Captures<'_, '_>, destination: &mut String);
+}
+
+impl Replacer for &str {
+    fn replace_append(&mut self, captures: &Captures<'_, '_>, destination: &mut String) {
+        captures.expand(self, destination);
+    }
+}
+
+impl Replacer for String {
+    fn replace_append(&mut self, captures: &Captures<'_, '_>, destination: &mut String) {
+        captures.expand(self, destination);
+    }
+}
+
+impl<F, T> Replacer for F
+where
+    F: FnMut(&Captures<'_, '_>) -> T,
+    T: AsRef<str>,
+{
+    fn replace_append(&mut self, captures: &Captures<'_, '_>, destination: &mut String) {
+        destination.push_str(self(captures).as_ref());
+    }
+}
+
+impl<'g, 'h> Captures<'g, 'h> {
+    /// Expand the template, appending the result to `destination`.
+    ///
+    /// `$1` or `${1}` is replaced by the text of group 1, and `$name` or `${name}` by the text of the group with that
+    /// name. Groups that don't exist or didn't participate in the match are replaced by nothing. `$$` is replaced by
+    /// a literal `$`, as is a `$` that doesn't start a reference.
+    ///
+    /// An unbraced reference takes as many letters, digits and `_` as it can, so use `${1}a` rather than `$1a` to
+    /// follow a group with text.
+    pub fn expand(&self, template: &str, destination: &mut String) {
+        let mut rest = template;
+
+        while let Some(index) = rest.find('$') {
+            destination.push_str(&rest[..index]);
+            rest = &rest[index + 1..];
+
+            let (reference, after) = if let Some(braced) = rest.strip_prefix('{') {
+                match braced.find('}') {
+                    Some(end) => (&braced[..end], &braced[end + 1..]),
+                    None => ("", rest),
+                }
+            } else {
+                let end = rest
+                    .find(|character: char| !(character.is_alphanumeric() || character == '_'))
+                    .unwrap_or(rest.len());
+
+                (&rest[..end], &rest[end..])
+            };
+
+            if reference.is_empty() {
+                // `$$`, or a `$` that isn't a reference, is a literal `$`.
+                destination.push('$');
+                rest = rest.strip_prefix('$').unwrap_or(rest);
+                continue;
+            }
+
+            let group = match reference.parse::<usize>() {
+                Ok(index) => self.get(index),
+                Err(_) => self.name(reference),
+            };
+
+            if let Some(group) = group {
+                destination.push_str(group.as_str());
+            }
+
+            rest = after;
+        }
+
+        destination.push_str(rest);
+    }
+}
@@ -0,0 +1,127 @@
+use super::{Rule, State};
+use crate::state::StateSet;
+
+/// The rules of a [Graph](super::Graph) indexed by the state they start from, so following rules only looks at the
+/// states involved rather than the whole graph.
+///
+/// Epsilon closures are precomputed for the states whose closures hold at most [MAX_STORED_CLOSURE_LEN] states, and
+/// followed afresh at every step for the rest: in a long chain of epsilon rules (e.g. `(a?){1000}`) most closures hold
+/// most of the graph, so storing them all would take time and memory quadratic in its size.
+#[derive(Clone)]
+pub(crate) struct RuleIndex {
+    /// The epsilon rules from each state, as indices into the graph's rules in order of preference.
+    epsilon_rules: Vec<Vec<usize>>,
+
+    /// The consuming rules from each state, as indices into the graph's rules in order of preference.
+    consuming_rules: Vec<Vec<usize>>,
+
+    /// The epsilon closure of each state, in the order they are followed, or [None] if it is too long to store.
+    epsilon_closures: Vec<Option<Vec<State>>>,
+}
+
+/// The most states an epsilon closure may hold to be precomputed.
+const MAX_STORED_CLOSURE_LEN: usize = 32;
+
+impl RuleIndex {
+    pub fn new(state_count: usize, rules: &[Rule]) -> Self {
+        let mut epsilon_rules = vec![Vec::new(); state_count];
+        let mut consuming_rules = vec![Vec::new(); state_count];
+
+        for (index, rule) in rules.iter().enumerate() {
+            let by_state = if rule.is_epsilon() {
+                &mut epsilon_rules
+            } else {
+                &mut consuming_rules
+            };
+
+            by_state[rule.start().index()].push(index);
+        }
+
+        // a closure is abandoned as soon as it grows too long, so this takes time linear in the graph.
+        let mut states = StateSet::new(state_count);
+
+        let epsilon_closures = (0..state_count)
+            .map(|index| {
+                states.clear();
+                states.insert(State::new(index));
+
+                follow_epsilon_rules(
+                    &epsilon_rules,
+                    rules,
+                    &mut states,
+                    0,
+                    MAX_STORED_CLOSURE_LEN,
+                )
+                .then(|| states.iter().collect())
+            })
+            .collect();
+
+        Self {
+            epsilon_rules,
+            consuming_rules,
+            epsilon_closures,
+        }
+    }
+
+    /// The epsilon rules from the state, as indices into the graph's rules in order of preference.
+    pub fn epsilon_rules(&self, state: State) -> &[usize] {
+        &self.epsilon_rules[state.index()]
+    }
+
+    /// The consuming rules from the state, as indices into the graph's rules in order of preference.
+    pub fn consuming_rules(&self, state: State) -> &[usize] {
+        &self.consuming_rules[state.index()]
+    }
+
+    /// Add the epsilon closure of the state - the states reachable from it by any number of epsilon rules
+    /// (including zero) - to the set.
+    ///
+    /// The rules must be the ones the index was built from, and the set must only contain whole epsilon closures,
+    /// e.g. by only ever being added to by this method.
+    pub fn add_epsilon_closure(&self, rules: &[Rule], state: State, states: &mut StateSet) {
+        // the set only ever gains whole closures, so a state already in it brings the rest of its closure.
+        if !states.insert(state) {
+            return;
+        }
+
+        match &self.epsilon_closures[state.index()] {
+            Some(closure) => {
+                for &state in closure {
+                    states.insert(state);
+                }
+            }
+            None => {
+                // the states added from here on are exactly the ones whose rules are yet to be followed.
+                let position = states.len() - 1;
+
+                follow_epsilon_rules(&self.epsilon_rules, rules, states, position, usize::MAX);
+            }
+        }
+    }
+}
+
+/// Follow the epsilon rules from the states in the set from the position onwards, adding the states they reach, until
+/// there are none left to follow or the set holds more than `max_len` states. Returns whether every rule was
+/// followed.
+fn follow_epsilon_rules(
+    epsilon_rules: &[Vec<usize>],
+    rules: &[Rule],
+    states: &mut StateSet,
+    mut position: usize,
+    max_len: usize,
+) -> bool {
+    // the set's insertion order serves as the work list.
+    while let Some(state) = states.get(position) {
+        if states.len() > max_len {
+            return false;
+        }
+
+        for &index in &epsilon_rules[state.index()] {
+            states.insert(rules[index].end());
+        }
+
+        position += 1;
+    }
+
+    states.len() <= max_len
+}
@@ -0,0 +1,366 @@
+use super::{Graph, Group, State};
+use crate::state::StateSet;
+use std::{collections::HashMap, mem::swap, ops::Range};
+
+/// A match of a [Graph] within a haystack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Match<'h> {
+    haystack: &'h str,
+    start: usize,
+    end: usize,
+}
+
+impl<'h> Match<'h> {
+    fn new(haystack: &'h str, start: usize, end: usize) -> Self {
+        Self {
+            haystack,
+            start,
+            end,
+        }
+    }
+
+    /// The byte offset of the start of the match.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// The byte offset just past the end of the match.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// The matched text.
+    pub fn as_str(&self) -> &'h str {
+        &self.haystack[self.range()]
+    }
+}
+
+/// The positions of a [Graph]'s capture groups within a match.
+pub struct Captures<'g, 'h> {
+    haystack: &'h str,
+
+    /// The start and end of each group, indexed by `2 * group` and `2 * group + 1`, with group 0 the whole match.
+    slots: Vec<Option<usize>>,
+
+    groups: &'g [Group],
+}
+
+impl<'g, 'h> Captures<'g, 'h> {
+    /// The match of the group, or [None] if the group did not participate in the match.
+    ///
+    /// Group 0 is the whole match, and is always present.
+    pub fn get(&self, group: usize) -> Option<Match<'h>> {
+        match self.slots.get(2 * group..2 * group + 2)? {
+            &[Some(start), Some(end)] => Some(Match::new(self.haystack, start, end)),
+            _ => None,
+        }
+    }
+
+    /// The match of the first group with the name, or [None] if there is no such group or it did not participate
+    /// in the match.
+    pub fn name(&self, name: &str) -> Option<Match<'h>> {
+        let index = self
+            .groups
+            .iter()
+            .position(|group| group.name.as_deref() == Some(name))?;
+
+        self.get(index + 1)
+    }
+
+    /// The number of groups, including group 0.
+    pub fn len(&self) -> usize {
+        self.slots.len() / 2
+    }
+
+    /// Always false, since group 0 is always present.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Iterate over the match of every group, including group 0.
+    pub fn iter(&self) -> impl Iterator<Item = Option<Match<'h>>> + '_ {
+        (0..self.len()).map(|group| self.get(group))
+    }
+}
+
+/// A thread of the [PikeVm]: a state of the [Graph], and the capture slots recorded on the way to it.
+struct Thread {
+    state: State,
+    slots: Vec<Option<usize>>,
+}
+
+/// The threads alive at one position in the haystack, in order of priority.
+///
+/// Each state is occupied by at most one thread - the highest priority one to reach it - which keeps the
+/// simulation linear in the length of the haystack.
+struct Threads {
+    threads: Vec<Thread>,
+    occupied: StateSet,
+}
+
+impl Threads {
+    fn new(state_count: usize) -> Self {
+        Self {
+            threads: Vec::new(),
+            occupied: StateSet::new(state_count),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.threads.clear();
+        self.occupied.clear();
+    }
+
+    fn is_empty(&self) -> bool {
+        self.threads.is_empty()
+    }
+}
+
+/// Simulates a [Graph] over a haystack with threads kept in priority order, giving leftmost-first search semantics.
+///
+/// Each thread carries its own capture slots, recording where it entered and left each group.
+pub(crate) struct PikeVm<'g> {
+    graph: &'g Graph,
+
+    /// The number of slots to track: 2 for just the whole match, or 2 per group as well when capturing.
+    slot_count: usize,
+
+    /// The slot recorded on entering each group's start or end state.
+    group_slots: HashMap<State, usize>,
+}
+
+impl<'g> PikeVm<'g> {
+    /// Create a [PikeVm] tracking the whole match only.
+    pub fn new(graph: &'g Graph) -> Self {
+        Self {
+            graph,
+            slot_count: 2,
+            group_slots: HashMap::new(),
+        }
+    }
+
+    /// Create a [PikeVm] tracking every capture group.
+    pub fn capturing(graph: &'g Graph) -> Self {
+        let group_slots = graph
+            .groups
+            .iter()
+            .enumerate()
+            .flat_map(|(index, group)| {
+                let slot = 2 * (index + 1);
+
+                group
+                    .boundaries
+                    .iter()
+                    .flat_map(move |&(start, end)| [(start, slot), (end, slot + 1)])
+            })
+            .collect();
+
+        Self {
+            graph,
+            slot_count: 2 * (graph.groups.len() + 1),
+            group_slots,
+        }
+    }
+
+    /// Find the leftmost-first match starting at or after the byte offset `start`.
+    pub fn find_at<'h>(&self, haystack: &'h str, start: usize) -> Option<Match<'h>> {
+        let slots = self.search(haystack, start)?;
+
+        Some(Match::new(haystack, slots[0]?, slots[1]?))
+    }
+
+    /// Find the leftmost-first match starting at or after the byte offset `start`, with its capture groups.
+    pub fn captures_at<'h>(&self, haystack: &'h str, start: usize) -> Option<Captures<'g, 'h>> {
+        let slots = self.search(haystack, start)?;
+
+        Some(Captures {
+            haystack,
+            slots,
+            groups: &self.graph.groups,
+        })
+    }
+
+    /// Run the search, returning the slots of the match.
+    fn search(&self, haystack: &str, start: usize) -> Option<Vec<Option<usize>>> {
+        let mut current = Threads::new(self.graph.state_count());
+        let mut next = Threads::new(self.graph.state_count());
+        let mut matched = None;
+
+        // visit every character boundary from `start`, including the end of the haystack.
+        let positions = haystack[start..]
+            .char_indices()
+            .map(|(index, character)| (start + index, Some(character)))
+            .chain([(haystack.len(), None)]);
+
+        for (position, character) in positions {
+            // start a new, lowest priority, thread here unless a match has already been found further left.
+            if matched.is_none() {
+                let mut slots = vec![None; self.slot_count];
+                slots[0] = Some(position);
+
+                self.add_thread(&mut current, self.graph.start, slots, position);
+            }
+
+            if current.is_empty() {
+                break;
+            }
+
+            for Thread { state, slots } in current.threads.drain(..) {
+                if state == self.graph.end {
+                    // lower priority threads can only produce less preferred matches, so drop them.
+                    let mut slots = slots;
+                    slots[1] = Some(position);
+
+                    matched = Some(slots);
+                    break;
+                }
+
+                let Some(character) = character else {
+                    continue;
+                };
+
+                let next_position = position + character.len_utf8();
+
+                for rule in self.graph.consuming_rules_from(state) {
+                    if rule.matches(character) {
+                        self.add_thread(&mut next, rule.end(), slots.clone(), next_position);
+                    }
+                }
+            }
+
+            swap(&mut current, &mut next);
+            next.clear();
+        }
+
+        matched
+    }
+
+    /// Add a thread in the state, followed by threads in every state reachable from it by epsilon rules, in order
+    /// of preference, recording the position in the slots of any group boundaries passed through.
+    fn add_thread(
+        &self,
+        threads: &mut Threads,
+        state: State,
+        slots: Vec<Option<usize>>,
+        position: usize,
+    ) {
+        let mut stack = vec![(state, slots)];
+
+        while let Some((state, mut slots)) = stack.pop() {
+            if !threads.occupied.insert(state) {
+                continue;
+            }
+
+            if let Some(&slot) = self.group_slots.get(&state) {
+                slots[slot] = Some(position);
+            }
+
+            // push in reverse so the most preferred rule is followed first.
+            let epsilon_rules: Vec<_> = self.graph.epsilon_rules_from(state).collect();
+
+            stack.extend(
+                epsilon_rules
+                    .into_iter()
+                    .rev()
+                    .map(|rule| (rule.end(), slots.clone())),
+            );
+
+            threads.threads.push(Thread { state, slots });
+        }
+    }
+}
+
+/// Successive non-overlapping searches of a haystack, shared by [FindIter] and [CapturesIter].
+struct Searches<'g, 'h> {
+    vm: PikeVm<'g>,
+    haystack: &'h str,
+
+    /// The byte offset to search from next, or [None] once the haystack is exhausted.
+    position: Option<usize>,
+
+    /// The end of the previous match, to avoid an empty match immediately after it.
+    last_match_end: Option<usize>,
+}
+
+impl<'g, 'h> Searches<'g, 'h> {
+    fn new(vm: PikeVm<'g>, haystack: &'h str) -> Self {
+        Self {
+            vm,
+            haystack,
+            position: Some(0),
+            last_match_end: None,
+        }
+    }
+
+    fn next_captures(&mut self) -> Option<Captures<'g, 'h>> {
+        loop {
+            let found = self.vm.captures_at(self.haystack, self.position?)?;
+            let whole = found.get(0).expect("group 0 should always be present");
+
+            self.position = if whole.is_empty() {
+                // step over the next character so the search makes progress.
+                self.haystack[whole.end..]
+                    .chars()
+                    .next()
+                    .map(|character| whole.end + character.len_utf8())
+            } else {
+                Some(whole.end)
+            };
+
+            // an empty match touching the end of the previous match is not a new match.
+            if whole.is_empty() && self.last_match_end == Some(whole.end) {
+                continue;
+            }
+
+            self.last_match_end = Some(whole.end);
+
+            return Some(found);
+        }
+    }
+}
+
+/// [Iterator] over the successive non-overlapping matches of a [Graph] in a haystack.
+pub struct FindIter<'g, 'h>(Searches<'g, 'h>);
+
+impl<'g, 'h> FindIter<'g, 'h> {
+    pub(crate) fn new(graph: &'g Graph, haystack: &'h str) -> Self {
+        Self(Searches::new(PikeVm::new(graph), haystack))
+    }
+}
+
+impl<'g, 'h> Iterator for FindIter<'g, 'h> {
+    type Item = Match<'h>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_captures()?.get(0)
+    }
+}
+
+/// [Iterator] over the capture groups of the successive non-overlapping matches of a [Graph] in a haystack.
+pub struct CapturesIter<'g, 'h>(Searches<'g, 'h>);
+
+impl<'g, 'h> CapturesIter<'g, 'h> {
+    pub(crate) fn new(graph: &'g Graph, haystack: &'h str) -> Self {
+        Self(Searches::new(PikeVm::capturing(graph), haystack))
+    }
+}
+
+impl<'g, 'h> Iterator for CapturesIter<'g, 'h> {
+    type Item = Captures<'g, 'h>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_captures()
+    }
+}
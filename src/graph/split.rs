@@ -0,0 +1,104 @@
+use super::search::FindIter;
+
+/// [Iterator] over the text between the matches of a [Graph](super::Graph) in a haystack.
+pub struct Split<'g, 'h> {
+    matches: FindIter<'g, 'h>,
+    haystack: &'h str,
+
+    /// The byte offset of the start of the next piece, or [None] once the last piece has been produced.
+    last_end: Option<usize>,
+}
+
+impl<'g, 'h> Split<'g, 'h> {
+    pub(crate) fn new(matches: FindIter<'g, 'h>, haystack: &'h str) -> Self {
+        Self {
+            matches,
+            haystack,
+            last_end: Some(0),
+        }
+    }
+
+    /// Produce the rest of the haystack as the final piece.
+    fn remainder(&mut self) -> Option<&'h str> {
+        self.last_end.take().map(|start| &self.haystack[start..])
+    }
+}
+
+impl<'g, 'h> Iterator for Split<'g, 'h> {
+    type Item = &'h str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.last_end?;
+
+        match self.matches.next() {
+            Some(found) => {
+                self.last_end = Some(found.end());
+
+                Some(&self.haystack[start..found.start()])
+            }
+            None => self.remainder(),
+        }
+    }
+}
+
+/// [Iterator] over at most a limited number of pieces of text between the matches of a [Graph](super::Graph), the
+/// last piece holding the rest of the haystack.
+pub struct SplitN<'g, 'h> {
+    split: Split<'g, 'h>,
+
+    /// The number of pieces left to produce.
+    remaining: usize,
+}
+
+impl<'g, 'h> SplitN<'g, 'h> {
+    pub(crate) fn new(split: Split<'g, 'h>, limit: usize) -> Self {
+        Self {
+            split,
+            remaining: limit,
+        }
+    }
+}
+
+impl<'g, 'h> Iterator for SplitN<'g, 'h> {
+    type Item = &'h str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining = self.remaining.checked_sub(1)?;
+
+        if self.remaining == 0 {
+            self.split.remainder()
+        } else {
+            self.split.next()
+        }
+    }
+}
+
+/// [Iterator] over the text between the matches of a [Graph](super::Graph) in a haystack, with each piece
+/// including the match that ends it.
+pub struct SplitInclusive<'g, 'h> {
+    split: Split<'g, 'h>,
+}
+
+impl<'g, 'h> SplitInclusive<'g, 'h> {
+    pub(crate) fn new(split: Split<'g, 'h>) -> Self {
+        Self { split }
+    }
+}
+
+impl<'g, 'h> Iterator for SplitInclusive<'g, 'h> {
+    type Item = &'h str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.split.last_end?;
+
+        match self.split.matches.next() {
+            Some(found) => {
+                self.split.last_end = Some(found.end());
+
+                Some(&self.split.haystack[start..found.end()])
+            }
+            // like `str::split_inclusive`, there is no empty piece after a trailing match.
+            None => self.split.remainder().filter(|rest| !rest.is_empty()),
+        }
+    }
+}
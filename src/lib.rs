@@ -1,3 +1,6 @@
+pub mod big_uint;
+pub mod char_class;
+pub mod dfa;
 pub mod graph;
 pub mod rule;
 pub mod state;
@@ -1,14 +1,9 @@
 use regex::graph::Graph;
-use std::time::Instant;
-
-/// the integer type used to count strings (for easy upgrading in case we want to count languages large enough to overflow).
-type Count = u64;
 
 fn main() {
     let default_options = StringCountOptions {
         max_string_length: 0,
         strings_to_print: None,
-        seconds_between_updates: 1,
     };
 
     // (lo)+l+
@@ -112,62 +107,33 @@ struct StringCountOptions {
     /// The maximum length of which to generate strings.
     max_string_length: usize,
 
-    /// The number of strings to print while counting (`Some(0)` prints all strings, `None` prints no strings).
+    /// The number of strings to print before the count (`Some(0)` prints all strings, `None` prints no strings).
     strings_to_print: Option<usize>,
-
-    /// The number of seconds between updates (0 prints no updates).
-    seconds_between_updates: u64,
 }
 
 fn print_string_count(graph: &Graph, options: StringCountOptions) {
     let StringCountOptions {
         max_string_length,
         strings_to_print,
-        seconds_between_updates,
     } = options;
 
     println!("========== {}", graph.label());
 
-    let count = graph
-        // produce the strings.
-        .produce_strings(max_string_length)
-        // get the index of each string.
-        .enumerate()
-        // print strings/updates (as per parameters).
-        .inspect({
-            let start_time = Instant::now();
-            let mut last_update = 0;
-
-            move |(i, string)| {
-                // print update.
-                if seconds_between_updates > 0 {
-                    let next_update = start_time.elapsed().as_secs() / seconds_between_updates;
-
-                    if next_update > last_update {
-                        println!("busy for {} seconds", next_update * seconds_between_updates);
-
-                        last_update = next_update;
-                    }
-                }
-
-                // print string.
-                if match strings_to_print {
-                    Some(0) => true,
-                    Some(strings_to_print) => *i < strings_to_print,
-                    None => false,
-                } {
-                    println!("{i} : \"{string}\"");
-                }
-            }
-        })
-        // get the count.
-        .fold(Some(0 as Count), |count, _| {
-            count.map(|count| count.checked_add(1)).flatten()
-        });
-
-    match count {
-        Some(count) => println!("string count: {}", count),
-        None => println!("overflowed! string count: more than {}", Count::MAX),
-    };
+    // print strings (as per parameters).
+    if let Some(strings_to_print) = strings_to_print {
+        let strings = graph.produce_strings(max_string_length);
+
+        let strings: Box<dyn Iterator<Item = String>> = match strings_to_print {
+            0 => Box::new(strings),
+            strings_to_print => Box::new(strings.take(strings_to_print)),
+        };
+
+        for (i, string) in strings.enumerate() {
+            println!("{i} : \"{string}\"");
+        }
+    }
+
+    // count the strings without producing them.
+    println!("string count: {}", graph.count_up_to(max_string_length));
     println!();
 }
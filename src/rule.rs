@@ -1,12 +1,11 @@
 use std::{
     fmt::{Debug, Formatter, Result as FmtResult},
     hash::{Hash, Hasher},
-    mem::discriminant,
 };
 
-use crate::state::State;
+use crate::{char_class::CharClass, state::State};
 
-#[derive(Hash)]
+#[derive(Clone)]
 pub struct Rule {
     start: State,
     end: State,
@@ -30,31 +29,42 @@ impl Rule {
         }
     }
 
-    pub fn lambda<F>(start: State, end: State, name: String, lambda: F) -> Self
-    where
-        F: Fn(char) -> bool + 'static,
-    {
+    pub fn class(start: State, end: State, class: CharClass) -> Self {
         Self {
             start,
             end,
-            matcher: Matcher::Lambda {
-                lambda: Box::new(lambda),
-                name,
-            },
+            matcher: Matcher::Class { class },
         }
     }
 
     pub fn match_eq(start: State, end: State, character_to_match: char) -> Self {
-        Self::lambda(
+        Self::class(start, end, CharClass::single(character_to_match))
+    }
+
+    pub fn match_any(start: State, end: State) -> Self {
+        Self::class(start, end, CharClass::any())
+    }
+
+    /// A copy of the rule between different states.
+    pub fn with_states(&self, start: State, end: State) -> Self {
+        Self {
             start,
             end,
-            format!("EQ {character_to_match}"),
-            move |character| character_to_match == character,
-        )
+            matcher: self.matcher.clone(),
+        }
     }
 
-    pub fn match_any(start: State, end: State) -> Self {
-        Self::lambda(start, end, "ANY".to_owned(), |_| true)
+    /// A rule between the given states matching the characters matched by both rules, or [None] if either is an
+    /// epsilon rule or no character can match both.
+    pub fn intersect(&self, other: &Self, start: State, end: State) -> Option<Self> {
+        match (&self.matcher, &other.matcher) {
+            (Matcher::Epsilon { .. }, _) | (_, Matcher::Epsilon { .. }) => None,
+            (Matcher::Class { class }, Matcher::Class { class: other_class }) => {
+                let class = class.intersect(other_class);
+
+                (!class.is_empty()).then(|| Self::class(start, end, class))
+            }
+        }
     }
 
     pub fn start(&self) -> State {
@@ -69,11 +79,18 @@ impl Rule {
         matches!(self.matcher, Matcher::Epsilon { .. })
     }
 
+    /// The [CharClass] matched by the rule, unless it is an epsilon rule.
+    pub fn char_class(&self) -> Option<&CharClass> {
+        match &self.matcher {
+            Matcher::Class { class } => Some(class),
+            Matcher::Epsilon { .. } => None,
+        }
+    }
+
     pub fn matches(&self, character: char) -> bool {
-        if let Matcher::Lambda { lambda, .. } = &self.matcher {
-            lambda(character)
-        } else {
-            false
+        match &self.matcher {
+            Matcher::Class { class } => class.contains(character),
+            Matcher::Epsilon { .. } => false,
         }
     }
 }
@@ -86,39 +103,41 @@ impl PartialEq for Rule {
 
 impl Eq for Rule {}
 
+impl Hash for Rule {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // must agree with `PartialEq`, which ignores the matcher.
+        self.start.hash(state);
+        self.end.hash(state);
+    }
+}
+
 impl Debug for Rule {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        // escape the label, since classes may contain quotes.
         write!(
             f,
-            "{:?} -> {:?} [label=\"{:?}\"]",
-            self.start, self.end, self.matcher
+            "{:?} -> {:?} [label={:?}]",
+            self.start,
+            self.end,
+            format!("{:?}", self.matcher)
         )
     }
 }
 
+#[derive(Clone)]
 enum Matcher {
-    Lambda {
-        lambda: Box<dyn Fn(char) -> bool>,
-        name: String,
-    },
-    Epsilon {
-        name: Option<String>,
-    },
-}
-
-impl Hash for Matcher {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        discriminant(self).hash(state);
-        if let Self::Lambda { name, .. } = self {
-            name.hash(state);
-        }
-    }
+    Class { class: CharClass },
+    Epsilon { name: Option<String> },
 }
 
 impl Debug for Matcher {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         match self {
-            Self::Lambda { name, .. } => write!(f, "{name}"),
+            Self::Class { class } if class.is_any() => write!(f, "ANY"),
+            Self::Class { class } => match class.as_single() {
+                Some(character) => write!(f, "EQ {character}"),
+                None => write!(f, "{class}"),
+            },
             Self::Epsilon { name: Some(name) } => write!(f, "{} (ε)", name),
             _ => write!(f, "ε"),
         }
@@ -1,20 +1,32 @@
 use std::{
     fmt::{Debug, Formatter, Result as FmtResult},
     hash::{Hash, Hasher},
-    sync::atomic::{AtomicU32, Ordering},
 };
 
-#[derive(PartialEq, Eq, Clone, Copy)]
+/// A state of a [Graph](crate::graph::Graph).
+///
+/// Each graph numbers its own states densely from 0, so a [State] only has meaning within its graph, and can index
+/// per-state storage directly.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub struct State(u32);
 
 impl State {
-    fn get_next_id() -> u32 {
-        static NEXT_ID: AtomicU32 = AtomicU32::new(0);
-        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    /// # Panics
+    ///
+    /// Panics if the index doesn't fit in a [u32].
+    pub fn new(index: usize) -> Self {
+        Self(u32::try_from(index).expect("state index should fit in a u32"))
     }
 
-    pub fn new() -> Self {
-        Self(Self::get_next_id())
+    /// The index of the state within its graph.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// The same state in a graph whose states start `offset` further along.
+    #[must_use]
+    pub(crate) fn shifted(self, offset: usize) -> Self {
+        Self::new(self.index() + offset)
     }
 }
 
@@ -29,3 +41,66 @@ impl Hash for State {
         state.write_u32(self.0);
     }
 }
+
+/// A set of the [State]s of one graph, stored as a sparse set: inserting, checking membership and clearing all take
+/// constant time, and iterating visits only the members, in the order they were inserted.
+///
+/// Clearing keeps the set's buffers, so a set can be reused for every step of a simulation without allocating.
+#[derive(Clone)]
+pub(crate) struct StateSet {
+    /// The members, in the order they were inserted.
+    dense: Vec<State>,
+
+    /// The position of each state in `dense`, which is only meaningful for members.
+    sparse: Vec<usize>,
+}
+
+impl StateSet {
+    /// Create an empty set able to hold the states of a graph with `state_count` states.
+    pub fn new(state_count: usize) -> Self {
+        Self {
+            dense: Vec::with_capacity(state_count),
+            sparse: vec![0; state_count],
+        }
+    }
+
+    pub fn contains(&self, state: State) -> bool {
+        self.sparse
+            .get(state.index())
+            .is_some_and(|&position| self.dense.get(position) == Some(&state))
+    }
+
+    /// Add the state to the set, returning whether it was newly added.
+    pub fn insert(&mut self, state: State) -> bool {
+        if self.contains(state) {
+            return false;
+        }
+
+        self.sparse[state.index()] = self.dense.len();
+        self.dense.push(state);
+
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.dense.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.dense.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dense.is_empty()
+    }
+
+    /// The member inserted at the position, counting from zero, if there are enough members.
+    pub fn get(&self, position: usize) -> Option<State> {
+        self.dense.get(position).copied()
+    }
+
+    /// Iterate over the members, in the order they were inserted.
+    pub fn iter(&self) -> impl Iterator<Item = State> + '_ {
+        self.dense.iter().copied()
+    }
+}
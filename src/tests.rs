@@ -1,2 +1,22 @@
+use crate::graph::Graph;
+
+mod analysis_tests;
+mod char_class_tests;
+mod compare_tests;
+mod count_tests;
+mod cover_tests;
+mod dfa_tests;
 mod match_tests;
+mod parse_tests;
+mod rank_tests;
+mod replace_tests;
+mod sample_tests;
+mod search_tests;
+mod split_tests;
+mod state_tests;
 mod strings_tests;
+
+/// Parse a pattern known to be valid.
+fn parse(pattern: &str) -> Graph {
+    Graph::parse(pattern).unwrap()
+}
@@ -0,0 +1,50 @@
+use super::parse;
+use crate::{char_class::CharClass, graph::Graph};
+
+#[test]
+fn test_is_empty_language() {
+    assert!(!parse("").is_empty_language());
+    assert!(!parse("a*").is_empty_language());
+    assert!(parse("a+").intersect(parse("b+")).is_empty_language());
+    assert!(Graph::class(CharClass::empty()).is_empty_language());
+    assert!(parse("a|b")
+        .concat(Graph::class(CharClass::empty()))
+        .is_empty_language());
+}
+
+#[test]
+fn test_is_finite() {
+    assert!(parse("").is_finite());
+    assert!(parse("(ab|c){0,3}d?").is_finite());
+    assert!(!parse("ab*").is_finite());
+
+    // a loop that can never lead to a match doesn't count.
+    assert!(parse("a|b+").intersect(parse("a|c")).is_finite());
+}
+
+#[test]
+fn test_shortest_match() {
+    assert_eq!(parse("").shortest_match().as_deref(), Some(""));
+    assert_eq!(
+        parse("(abc|de)+|xyzw").shortest_match().as_deref(),
+        Some("de")
+    );
+    assert_eq!(parse("[q-z]{2}|z").shortest_match().as_deref(), Some("z"));
+    assert_eq!(parse("[q-z]{2}").shortest_match().as_deref(), Some("qq"));
+    assert_eq!(parse("a").intersect(parse("b")).shortest_match(), None);
+}
+
+#[test]
+fn test_longest_match() {
+    assert_eq!(parse("").longest_match().as_deref(), Some(""));
+    assert_eq!(
+        parse("(ab|c){0,3}d?").longest_match().as_deref(),
+        Some("abababd")
+    );
+    assert_eq!(
+        parse("[x-z]{2}|abc").longest_match().as_deref(),
+        Some("abc")
+    );
+    assert_eq!(parse("a+").longest_match(), None);
+    assert_eq!(parse("a").intersect(parse("b")).longest_match(), None);
+}
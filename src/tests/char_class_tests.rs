@@ -0,0 +1,73 @@
+use crate::char_class::CharClass;
+
+#[test]
+fn test_normalize() {
+    let class = CharClass::new(['x'..='z', 'a'..='c', 'b'..='f', 'g'..='g', 'q'..='p']);
+
+    assert_eq!(class.ranges().collect::<Vec<_>>(), ['a'..='g', 'x'..='z']);
+    assert_eq!(class.len(), 10);
+    assert_eq!(class.to_string(), "[a-gx-z]");
+}
+
+#[test]
+fn test_contains() {
+    let class = CharClass::word();
+
+    for character in ['0', '9', 'A', 'Z', '_', 'a', 'm', 'z'] {
+        assert!(class.contains(character), "{character}");
+    }
+
+    for character in ['/', ':', '@', '[', '`', '{', '\u{E9}'] {
+        assert!(!class.contains(character), "{character}");
+    }
+}
+
+#[test]
+fn test_next_after() {
+    let class = CharClass::new(['b'..='d', 'x'..='x', '\u{D7FF}'..='\u{E001}']);
+
+    assert_eq!(class.next_after('a'), Some('b'));
+    assert_eq!(class.next_after('b'), Some('c'));
+    assert_eq!(class.next_after('d'), Some('x'));
+    assert_eq!(class.next_after('\u{D7FF}'), Some('\u{E000}'));
+    assert_eq!(class.next_after('\u{E001}'), None);
+    assert_eq!(CharClass::any().next_after(char::MAX), None);
+}
+
+#[test]
+fn test_negate() {
+    let class = CharClass::new(['\0'..='a', 'c'..='\u{D7FF}']);
+    let negated = class.negate();
+
+    assert_eq!(
+        negated.ranges().collect::<Vec<_>>(),
+        ['b'..='b', '\u{E000}'..=char::MAX]
+    );
+    assert_eq!(negated.negate(), class);
+    assert!(CharClass::empty().negate().is_any());
+    assert!(CharClass::any().negate().is_empty());
+}
+
+#[test]
+fn test_set_operations() {
+    let left = CharClass::new(['a'..='m', 'x'..='z']);
+    let right = CharClass::new(['k'..='y']);
+
+    assert_eq!(left.union(&right), CharClass::new(['a'..='z']));
+    assert_eq!(
+        left.intersect(&right),
+        CharClass::new(['k'..='m', 'x'..='y'])
+    );
+    assert_eq!(
+        left.difference(&right),
+        CharClass::new(['a'..='j', 'z'..='z'])
+    );
+    assert!(left.intersect(&CharClass::digit()).is_empty());
+}
+
+#[test]
+fn test_display() {
+    let class: CharClass = "-]^a\t".chars().collect();
+
+    assert_eq!(class.to_string(), r"[\t\-\]-\^a]");
+}
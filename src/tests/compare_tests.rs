@@ -0,0 +1,60 @@
+use super::parse;
+
+#[test]
+fn test_equivalent() {
+    let cases = [
+        ("(a|b)*", "(a*b*)*"),
+        ("a{2,}", "aaa*"),
+        (r"\d+", "[0-9][0-9]*"),
+        ("(ab)*a", "a(ba)*"),
+        ("(?<x>a)|b", "[ab]"),
+    ];
+
+    for (left, right) in cases {
+        assert_eq!(
+            parse(left).is_equivalent(&parse(right)),
+            Ok(()),
+            "{left} vs {right}"
+        );
+    }
+}
+
+#[test]
+fn test_not_equivalent() {
+    let cases = [
+        ("a*", "a+", "", true),
+        ("a+", "a*", "", false),
+        ("(a|b)*", "(ab)*", "a", true),
+        ("[a-z]+", r"\w+", "0", false),
+        ("colou?r", "color", "colour", true),
+    ];
+
+    for (left, right, string, matched_by_self) in cases {
+        let counterexample = parse(left).is_equivalent(&parse(right)).unwrap_err();
+
+        assert_eq!(
+            (counterexample.string(), counterexample.matched_by_self()),
+            (string, matched_by_self),
+            "{left} vs {right}",
+        );
+    }
+}
+
+#[test]
+fn test_subset() {
+    assert_eq!(parse("a+").is_subset_of(&parse("a*")), Ok(()));
+    assert_eq!(parse("ab|ac").is_subset_of(&parse("a[b-z]")), Ok(()));
+
+    let counterexample = parse("a*").is_subset_of(&parse("a+")).unwrap_err();
+    assert_eq!(counterexample.string(), "");
+    assert!(counterexample.matched_by_self());
+
+    let counterexample = parse("[a-c]{2}")
+        .is_subset_of(&parse("ab|ba|c."))
+        .unwrap_err();
+    assert_eq!(counterexample.string(), "aa");
+    assert_eq!(
+        counterexample.to_string(),
+        "\"aa\" is matched by self but not by other"
+    );
+}
@@ -0,0 +1,70 @@
+use super::parse;
+use crate::{big_uint::BigUint, graph::Graph};
+
+/// Count the strings of each length by enumerating them, to check the counts against.
+fn enumerated_counts(graph: &Graph, max_len: usize) -> Vec<u64> {
+    let mut counts = vec![0; max_len + 1];
+
+    for string in graph.produce_strings(max_len) {
+        counts[string.chars().count()] += 1;
+    }
+
+    counts
+}
+
+#[test]
+fn test_count_strings() {
+    for pattern in [
+        "",
+        "a*",
+        "(lo)+l+",
+        "hah+a*",
+        "((ab?|c?d)+|e+)+",
+        "(a|ab)(c|bcd)",
+    ] {
+        let graph = parse(pattern);
+
+        for (len, count) in enumerated_counts(&graph, 6).into_iter().enumerate() {
+            assert_eq!(
+                graph.count_strings(len).to_u64(),
+                Some(count),
+                "{pattern} at length {len}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_count_classes() {
+    assert_eq!(parse("[a-z0-9]").count_strings(1).to_u64(), Some(36));
+    assert_eq!(parse("[a-c]{2}x?").count_strings(3).to_u64(), Some(9));
+    assert_eq!(parse(".").count_strings(1).to_u64(), Some(0x110000 - 0x800));
+    assert_eq!(
+        parse("[^a]").count_strings(1).to_u64(),
+        Some(0x110000 - 0x801)
+    );
+}
+
+#[test]
+fn test_count_up_to() {
+    assert_eq!(parse("(lo)+l+").count_up_to(571).to_u64(), Some(81225));
+    assert_eq!(parse("a{2,4}").count_up_to(3).to_u64(), Some(2));
+    assert!(parse("a").intersect(parse("b")).count_up_to(10).is_zero());
+}
+
+#[test]
+fn test_count_overflow() {
+    let count = parse("[a-z]*").count_strings(30);
+
+    assert_eq!(count.to_u64(), None);
+    assert_eq!(
+        count.to_string(),
+        "2813198901284745919258621029615971520741376"
+    );
+    assert_eq!(
+        (count.clone() + &BigUint::one()).to_string(),
+        "2813198901284745919258621029615971520741377"
+    );
+    assert!(count > BigUint::from(u64::MAX));
+    assert_eq!(BigUint::zero().to_string(), "0");
+}
@@ -0,0 +1,60 @@
+use super::parse;
+use crate::{
+    char_class::CharClass,
+    graph::{cover::Coverage, Graph},
+};
+
+#[test]
+fn test_covering_strings() {
+    assert_eq!(parse("a|b|cd").covering_strings(), ["a", "b", "cd"]);
+    assert_eq!(parse("(a|b)(c|d)").covering_strings(), ["ad", "bd", "bc"]);
+
+    // repeating the `b` is the only way to traverse the rule looping back.
+    assert_eq!(parse("ab*c").covering_strings(), ["ac", "abc", "abbc"]);
+}
+
+#[test]
+fn test_covering_strings_match() {
+    for pattern in [
+        "x(ab|c)*y",
+        "[a-c]+|z{2}",
+        r"\d{2,3}-?\w",
+        "((ab?|c?d)+|e+)+",
+    ] {
+        let graph = parse(pattern);
+
+        for coverage in [Coverage::Rules, Coverage::RulePairs] {
+            let strings = graph.covering_strings_with(coverage);
+
+            assert!(!strings.is_empty());
+            assert!(
+                strings.iter().all(|string| graph.matches(string)),
+                "{pattern}: {strings:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_covering_rule_pairs() {
+    // the pairs of rules through the loop are all traversed by repeating it, so one string is enough besides "".
+    assert_eq!(
+        parse("a*").covering_strings_with(Coverage::RulePairs),
+        ["aa", ""]
+    );
+    assert_eq!(
+        parse("ab*c").covering_strings_with(Coverage::RulePairs),
+        ["ac", "abbc"]
+    );
+}
+
+#[test]
+fn test_unmatchable_rules() {
+    // rules which can't take part in a match are skipped.
+    let graph = parse("a|b").intersect(parse("b|c"));
+
+    assert_eq!(graph.covering_strings(), ["b"]);
+    assert!(Graph::class(CharClass::empty())
+        .covering_strings()
+        .is_empty());
+}
@@ -0,0 +1,205 @@
+use super::parse;
+use crate::{
+    char_class::CharClass,
+    dfa::{alphabet::Alphabet, lazy::LazyDfaConfig, Dfa},
+    graph::Graph,
+};
+
+/// Check the [Dfa] agrees with the [Graph] on every string over the alphabet up to the given length.
+fn do_test(pattern: &str, alphabet: &str, max_len: usize) -> Dfa {
+    let graph = parse(pattern);
+    let dfa = graph.to_dfa();
+
+    let mut strings = vec![String::new()];
+
+    for _ in 0..max_len {
+        strings = strings
+            .iter()
+            .flat_map(|string| {
+                alphabet
+                    .chars()
+                    .map(move |character| format!("{string}{character}"))
+            })
+            .chain(strings.iter().cloned())
+            .collect();
+    }
+
+    for string in strings {
+        assert_eq!(
+            dfa.matches(&string),
+            graph.matches(&string),
+            "pattern: {pattern}, string: {string:?}\n{dfa:?}",
+        );
+    }
+
+    dfa
+}
+
+#[test]
+fn test_literals() {
+    let dfa = do_test("abc", "abcd", 4);
+
+    // dead, start, and one state per character consumed.
+    assert_eq!(dfa.state_count(), 5);
+}
+
+#[test]
+fn test_repetition() {
+    do_test("(ab?|c?d)+|e+", "abcde", 5);
+}
+
+#[test]
+fn test_classes() {
+    do_test(r"[a-c]x|[b-d]y|\w+", "abcdxy_-", 3);
+}
+
+#[test]
+fn test_any() {
+    do_test("a.*b", "ab\u{E9}\u{1F600}", 4);
+}
+
+#[test]
+fn test_empty() {
+    let dfa = do_test("", "a", 2);
+
+    assert!(dfa.is_accepting(dfa.start()));
+    assert_eq!(dfa.next_state(dfa.start(), 'a'), Dfa::DEAD);
+}
+
+#[test]
+fn test_alphabet() {
+    let lower = CharClass::new(['a'..='z']);
+    let vowels: CharClass = "aeiou".chars().collect();
+
+    let alphabet = Alphabet::new([&lower, &vowels]);
+
+    // outside `a-z`, consonants, and vowels.
+    assert_eq!(alphabet.len(), 3);
+    assert_eq!(alphabet.class_of('\0'), alphabet.class_of('\u{1F600}'));
+    assert_eq!(alphabet.class_of('a'), alphabet.class_of('u'));
+    assert_eq!(alphabet.class_of('b'), alphabet.class_of('z'));
+    assert_ne!(alphabet.class_of('a'), alphabet.class_of('b'));
+    assert_eq!(alphabet.class(alphabet.class_of('e')), &vowels);
+}
+
+#[test]
+fn test_minimize() {
+    let dfa = do_test("(a|b)*abb", "abc", 5);
+    let minimal = dfa.minimize();
+
+    // the four states of the textbook automaton, plus the dead state.
+    assert_eq!(minimal.state_count(), 5);
+
+    for string in ["abb", "aabb", "babb", "ab", "abba", "abbc", ""] {
+        assert_eq!(minimal.matches(string), dfa.matches(string), "{string}");
+    }
+}
+
+#[test]
+fn test_minimize_canonical() {
+    let cases = [
+        ("(a|b)*", "(a*b*)*"),
+        ("a+", "aa*"),
+        ("[ab]c|bc", "(a|b)c"),
+        ("a|a", "[a]"),
+        ("(ab)*a", "a(ba)*"),
+    ];
+
+    for (left, right) in cases {
+        let left_dfa = parse(left).to_dfa().minimize();
+        let right_dfa = parse(right).to_dfa().minimize();
+
+        assert_eq!(left_dfa, right_dfa, "{left} vs {right}");
+    }
+
+    let left_dfa = parse("a*").to_dfa().minimize();
+    let right_dfa = parse("a+").to_dfa().minimize();
+
+    assert_ne!(left_dfa, right_dfa);
+}
+
+#[test]
+fn test_minimize_empty_language() {
+    let graph = Graph::from('a').concat(Graph::class(CharClass::empty()));
+    let minimal = graph.to_dfa().minimize();
+
+    assert_eq!(minimal.start(), Dfa::DEAD);
+    assert_eq!(minimal.state_count(), 1);
+    assert_eq!(minimal.alphabet().len(), 1);
+}
+
+/// `(a|b)*a(a|b)(a|b)...`, whose full [Dfa] has `2^(n+1)` states.
+fn nth_from_last_is_a(n: usize) -> Graph {
+    (0..n).fold(parse("(a|b)*a"), |graph, _| graph.concat(parse("a|b")))
+}
+
+/// Every string over `ab` of the given length, read off the bits of its index.
+fn binary_strings(len: usize) -> impl Iterator<Item = String> {
+    (0..1 << len).map(move |bits: u32| {
+        (0..len)
+            .map(|bit| if bits >> bit & 1 == 1 { 'a' } else { 'b' })
+            .collect()
+    })
+}
+
+#[test]
+fn test_lazy() {
+    let graph = nth_from_last_is_a(3);
+    let mut lazy = graph.to_lazy_dfa(LazyDfaConfig::default());
+
+    for string in (0..8).flat_map(binary_strings) {
+        assert_eq!(lazy.matches(&string), graph.matches(&string), "{string}");
+    }
+
+    assert!(!lazy.matches("abcab"));
+    assert_eq!(lazy.cache_clears(), 0);
+    assert_eq!(lazy.fallbacks(), 0);
+
+    // every determinized state of the full automaton was reached, but no more.
+    assert_eq!(lazy.cached_state_count(), graph.to_dfa().state_count());
+}
+
+#[test]
+fn test_lazy_cache_budget() {
+    let graph = nth_from_last_is_a(12);
+    let config = LazyDfaConfig {
+        memory_budget: 1024,
+        max_cache_clears: 2,
+    };
+    let mut lazy = graph.to_lazy_dfa(config);
+
+    for string in binary_strings(14).step_by(97) {
+        assert_eq!(lazy.matches(&string), graph.matches(&string), "{string}");
+    }
+
+    assert!(lazy.cache_clears() > 0);
+    assert!(lazy.fallbacks() > 0);
+}
+
+#[test]
+fn test_lazy_cached_transition_within_budget() {
+    let graph = parse("a*");
+
+    let lazy_dfa = |memory_budget| {
+        graph.to_lazy_dfa(LazyDfaConfig {
+            memory_budget,
+            max_cache_clears: 0,
+        })
+    };
+
+    // the smallest budget holding both states reached by `a`.
+    let memory_budget = (0..)
+        .find(|&memory_budget| {
+            let mut lazy = lazy_dfa(memory_budget);
+            lazy.matches("a");
+            lazy.fallbacks() == 0
+        })
+        .unwrap();
+
+    // every further `a` leads back to a cached state, which needs no room.
+    let mut lazy = lazy_dfa(memory_budget);
+
+    assert!(lazy.matches("aaaaaaaa"));
+    assert_eq!(lazy.cache_clears(), 0);
+    assert_eq!(lazy.fallbacks(), 0);
+}
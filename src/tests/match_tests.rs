@@ -1,4 +1,5 @@
-use crate::graph::Graph;
+use super::parse;
+use crate::{char_class::CharClass, graph::Graph};
 
 fn do_test(graph: &Graph, cases: &[(&str, bool)]) {
     let (passed, fail_message) = cases.iter().copied().fold(
@@ -163,3 +164,172 @@ fn test_optional_1() {
         &[("hi", true), ("h", false), ("i", false), ("ho", false)],
     );
 }
+
+#[test]
+fn test_repeat() {
+    let graph = Graph::from('h').concat(Graph::from('a').repeat(1, Some(3)));
+
+    do_test(
+        &graph,
+        &[("ha", true), ("haaa", true), ("h", false), ("haaaa", false)],
+    );
+}
+
+#[test]
+fn test_clone() {
+    let ab = Graph::from('a').concat(Graph::from('b'));
+    let graph = ab.clone().concat(ab.one_or_more());
+
+    do_test(
+        &graph,
+        &[
+            ("abab", true),
+            ("ababab", true),
+            ("ab", false),
+            ("aba", false),
+        ],
+    );
+}
+
+#[test]
+fn test_intersect() {
+    // a valid identifier, no longer than 5 characters.
+    let identifier = parse(r"[a-z_]\w*");
+    let short = parse(".{0,5}");
+
+    let graph = identifier.intersect(short);
+
+    do_test(
+        &graph,
+        &[
+            ("x", true),
+            ("_tmp1", true),
+            ("", false),
+            ("tmp123", false),
+            ("1abc", false),
+        ],
+    );
+}
+
+#[test]
+fn test_intersect_disjoint() {
+    let graph = parse("a+").intersect(parse("b*"));
+
+    do_test(&graph, &[("", false), ("a", false), ("b", false)]);
+}
+
+#[test]
+fn test_complement() {
+    let lower = CharClass::new(['a'..='z']);
+    let graph = parse("a*").complement(&lower);
+
+    do_test(
+        &graph,
+        &[
+            ("b", true),
+            ("aab", true),
+            ("ba", true),
+            ("", false),
+            ("aaa", false),
+            ("A", false),
+            ("aA", false),
+        ],
+    );
+
+    // complementing twice gives back the original language, restricted to the alphabet.
+    let graph = graph.complement(&lower);
+
+    do_test(
+        &graph,
+        &[("", true), ("aa", true), ("b", false), ("A", false)],
+    );
+}
+
+#[test]
+fn test_difference() {
+    // anything email-like, except the reserved local parts.
+    let email = parse(r"\w+@\w+\.\w+");
+    let reserved = parse(r"(admin|root)@.*");
+
+    let graph = email.difference(reserved);
+
+    do_test(
+        &graph,
+        &[
+            ("ada@example.com", true),
+            ("admins@example.com", true),
+            ("admin@example.com", false),
+            ("root@example.com", false),
+            ("not an email", false),
+        ],
+    );
+}
+
+#[test]
+fn test_epsilon_cycles() {
+    // the inner graph matches the empty string, so the outer repetition forms a cycle of epsilon rules.
+    let graph = parse("(a*|b?)*c");
+
+    do_test(
+        &graph,
+        &[
+            ("c", true),
+            ("abac", true),
+            ("bbbc", true),
+            ("ab", false),
+            ("", false),
+        ],
+    );
+}
+
+#[test]
+fn test_large_graph() {
+    // the classic pathological pattern: every state is live at once.
+    let graph = parse("(a?){300}a{300}");
+
+    do_test(
+        &graph,
+        &[
+            (&"a".repeat(300), true),
+            (&"a".repeat(450), true),
+            (&"a".repeat(600), true),
+            (&"a".repeat(299), false),
+            (&"a".repeat(601), false),
+        ],
+    );
+}
+
+#[test]
+fn test_long_epsilon_chain() {
+    // most states' epsilon closures hold most of the graph, so they are too long to store.
+    let graph = parse("(a?){2000}b");
+
+    do_test(
+        &graph,
+        &[
+            ("b", true),
+            ("aab", true),
+            (&format!("{}b", "a".repeat(500)), true),
+            ("aa", false),
+            ("aba", false),
+        ],
+    );
+}
+
+#[test]
+fn test_stored_and_followed_closures() {
+    // the closures in the first half are short enough to store, and the ones in the second half are not.
+    let graph = parse("(x|y?z)*(a?){100}b");
+
+    do_test(
+        &graph,
+        &[
+            ("b", true),
+            ("xzyzab", true),
+            (&format!("zx{}b", "a".repeat(100)), true),
+            (&format!("x{}b", "a".repeat(101)), false),
+            ("yb", false),
+            ("xza", false),
+        ],
+    );
+}
@@ -0,0 +1,222 @@
+use super::parse;
+use crate::graph::{parse::ParseErrorKind, Graph};
+
+fn do_test(pattern: &str, cases: &[(&str, bool)]) {
+    let graph = parse(pattern);
+
+    let failed: Vec<_> = cases
+        .iter()
+        .copied()
+        .filter(|&(string, expected)| graph.matches(string) != expected)
+        .collect();
+
+    assert!(
+        failed.is_empty(),
+        "***** pattern: {pattern}\n\n***** graph:\n{:?}\n\n***** failed cases:\n{:#?}",
+        &graph,
+        failed,
+    );
+}
+
+#[test]
+fn test_literals() {
+    do_test("hi", &[("hi", true), ("h", false), ("hii", false)]);
+}
+
+#[test]
+fn test_empty() {
+    do_test("", &[("", true), ("a", false)]);
+}
+
+#[test]
+fn test_any() {
+    do_test(
+        "h.",
+        &[("hi", true), ("h!", true), ("h", false), ("hi!", false)],
+    );
+}
+
+#[test]
+fn test_quantifiers() {
+    do_test(
+        "ab*c+d?",
+        &[
+            ("ac", true),
+            ("abbccd", true),
+            ("acd", true),
+            ("ab", false),
+            ("acdd", false),
+        ],
+    );
+}
+
+#[test]
+fn test_alternation() {
+    do_test(
+        "hi|lo|",
+        &[("hi", true), ("lo", true), ("", true), ("hilo", false)],
+    );
+}
+
+#[test]
+fn test_groups() {
+    do_test(
+        "(ab?|c?d)+|e+",
+        &[
+            ("a", true),
+            ("abd", true),
+            ("cdab", true),
+            ("eee", true),
+            ("ae", false),
+            ("c", false),
+            ("", false),
+        ],
+    );
+}
+
+#[test]
+fn test_escapes() {
+    do_test(r"\(\.\)\\\t", &[("(.)\\\t", true), ("(a)\\\t", false)]);
+}
+
+#[test]
+fn test_classes() {
+    do_test(
+        r"[a-c_][^abc]\d[\w-]",
+        &[
+            ("_d0-", true),
+            ("ax9Z", true),
+            ("a\u{1F600}5_", true),
+            ("da0a", false),
+            ("aa0a", false),
+            ("a!a!", false),
+        ],
+    );
+}
+
+#[test]
+fn test_class_escapes() {
+    do_test(
+        r"[\]\-^]\S\s",
+        &[
+            ("]x ", true),
+            ("-x\t", true),
+            ("^!\n", true),
+            ("a  ", false),
+            ("\\x ", false),
+        ],
+    );
+}
+
+#[test]
+fn test_group_syntax() {
+    do_test(
+        "(?:ab)+(?<c>c)?(?P<d>d)",
+        &[
+            ("abd", true),
+            ("ababcd", true),
+            ("abcc", false),
+            ("d", false),
+        ],
+    );
+}
+
+#[test]
+fn test_from_str() {
+    let graph: Graph = "(lo)+l+".parse().unwrap();
+
+    assert!(graph.matches("lolol"));
+    assert!(!graph.matches("lo"));
+}
+
+#[test]
+fn test_errors() {
+    let cases = [
+        ("a(b|", ParseErrorKind::UnclosedGroup, 1..2),
+        ("ab)", ParseErrorKind::UnopenedGroup, 2..3),
+        ("*a", ParseErrorKind::MissingRepeatOperand, 0..1),
+        ("a|+", ParseErrorKind::MissingRepeatOperand, 2..3),
+        ("(?a)", ParseErrorKind::MissingRepeatOperand, 1..2),
+        (r"a\q", ParseErrorKind::InvalidEscape('q'), 1..3),
+        (r"a\", ParseErrorKind::TrailingBackslash, 1..2),
+        ("a[bc", ParseErrorKind::UnclosedClass, 1..2),
+        ("a[]", ParseErrorKind::EmptyClass, 1..3),
+        (r"[^\s\S]", ParseErrorKind::EmptyClass, 0..7),
+        ("[az-a]", ParseErrorKind::InvalidClassRange, 2..5),
+        (r"[a-\d]", ParseErrorKind::InvalidClassRange, 1..5),
+        (r"[\d-z]", ParseErrorKind::InvalidClassRange, 1..5),
+        (r"[x\w-\d]", ParseErrorKind::InvalidClassRange, 2..7),
+        ("(?<>a)", ParseErrorKind::InvalidGroupName, 0..4),
+        ("{2}", ParseErrorKind::MissingRepeatOperand, 0..1),
+        ("a{3,2}", ParseErrorKind::InvalidRepetition, 1..6),
+        ("a{,2}", ParseErrorKind::InvalidRepetition, 1..5),
+        ("a{2", ParseErrorKind::InvalidRepetition, 1..3),
+        ("a{x}", ParseErrorKind::InvalidRepetition, 1..2),
+        ("(?P<1st>a)", ParseErrorKind::InvalidGroupName, 0..8),
+        ("a(?<name", ParseErrorKind::InvalidGroupName, 1..8),
+        ("a{4294967296}", ParseErrorKind::RepetitionTooLarge, 1..13),
+        (
+            "a{18446744073709551615,}",
+            ParseErrorKind::RepetitionTooLarge,
+            1..24,
+        ),
+        ("(a{1000}){1000}", ParseErrorKind::RepetitionTooLarge, 9..15),
+    ];
+
+    for (pattern, kind, span) in cases {
+        let error = Graph::parse(pattern).unwrap_err();
+
+        assert_eq!((error.kind(), error.span()), (kind, span), "{pattern}");
+    }
+}
+
+#[test]
+fn test_error_display() {
+    let error = Graph::parse("é(b|").unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "error: unclosed group\n  |\n  | é(b|\n  |  ^",
+    );
+}
+
+#[test]
+fn test_repetition() {
+    do_test(
+        "a{2}b{1,}c{0,2}",
+        &[
+            ("aab", true),
+            ("aabbbcc", true),
+            ("ab", false),
+            ("aaab", false),
+            ("aa", false),
+            ("aabccc", false),
+        ],
+    );
+
+    do_test(
+        "(ab|c){2,3}",
+        &[
+            ("abc", true),
+            ("ccab", true),
+            ("c", false),
+            ("abababab", false),
+        ],
+    );
+    do_test("x{0}y", &[("y", true), ("xy", false)]);
+}
+
+#[test]
+fn test_large_repetition() {
+    // each copy is added once, so the graph grows linearly with the bound.
+    let graph = parse("a{1,5000}");
+
+    assert!(graph.state_count() <= 3 * 5000, "{}", graph.state_count());
+    assert_eq!(graph.label(), "(a){1,5000}");
+
+    let graph = parse("(?<x>ab){1000}");
+
+    assert!(graph.matches(&"ab".repeat(1000)));
+    assert!(!graph.matches(&"ab".repeat(999)));
+    assert!(!graph.matches(&"ab".repeat(1001)));
+}
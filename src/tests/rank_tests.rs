@@ -0,0 +1,102 @@
+use super::parse;
+use crate::{big_uint::BigUint, graph::Graph};
+
+/// The strings matched by the graph up to the length, in shortlex order.
+fn shortlex(graph: &Graph, max_len: usize) -> Vec<String> {
+    let mut strings: Vec<_> = graph.produce_strings(max_len).collect();
+    strings.sort_by(|a, b| a.chars().count().cmp(&b.chars().count()).then(a.cmp(b)));
+    strings
+}
+
+#[test]
+fn test_nth_string() {
+    for pattern in ["a*b*", "(lo)+l+", "((ab?|c?d)+|e+)+", "[x-z]|[a-c]{2}"] {
+        let graph = parse(pattern);
+        let dfa = graph.to_dfa().minimize();
+
+        for (n, expected) in shortlex(&graph, 5).into_iter().enumerate() {
+            let n = BigUint::from(n as u64);
+
+            assert_eq!(dfa.nth_string(&n), Some(expected.clone()), "{pattern}");
+            assert_eq!(dfa.rank(&expected), Some(n), "{pattern}");
+        }
+    }
+}
+
+#[test]
+fn test_finite_language() {
+    let graph = parse("a|b|cd");
+
+    assert_eq!(graph.nth_string(&BigUint::from(2)).as_deref(), Some("cd"));
+    assert_eq!(graph.nth_string(&BigUint::from(3)), None);
+    assert_eq!(
+        parse("a")
+            .intersect(parse("b"))
+            .nth_string(&BigUint::zero()),
+        None
+    );
+}
+
+#[test]
+fn test_rank_not_matched() {
+    let graph = parse("a+");
+
+    assert_eq!(graph.rank(""), None);
+    assert_eq!(graph.rank("ab"), None);
+    assert_eq!(graph.rank("aaa"), Some(BigUint::from(2)));
+}
+
+#[test]
+fn test_surrogate_gap() {
+    let graph = parse(".");
+    let after_gap = BigUint::from(0xD800);
+
+    assert_eq!(graph.nth_string(&after_gap).as_deref(), Some("\u{E000}"));
+    assert_eq!(graph.rank("\u{E000}"), Some(after_gap));
+    assert_eq!(graph.rank("\u{D7FF}"), Some(BigUint::from(0xD7FF)));
+}
+
+#[test]
+fn test_large_index() {
+    let graph = parse("[a-z]*");
+
+    // all 26^20 strings of length 20 come before "a" repeated 21 times, along with all shorter strings.
+    let n = graph.count_up_to(20);
+    let string = "a".repeat(21);
+
+    assert_eq!(graph.nth_string(&n), Some(string.clone()));
+    assert_eq!(graph.rank(&string), Some(n.clone()));
+
+    let n = n + &BigUint::from(12345);
+    let string = graph.nth_string(&n).unwrap();
+
+    assert_eq!(graph.rank(&string), Some(n));
+}
+
+#[test]
+fn test_div_rem() {
+    let dividend = parse("[a-z]*").count_strings(30);
+    let divisor = parse("[a-z]*").count_strings(12) + &BigUint::from(7);
+
+    let (quotient, remainder) = dividend.div_rem(&divisor);
+
+    assert!(remainder < divisor);
+
+    let mut product = BigUint::zero();
+    let mut multiple = divisor.clone();
+    let mut quotient = quotient;
+
+    // rebuild quotient * divisor + remainder by binary multiplication.
+    while !quotient.is_zero() {
+        let (half, bit) = quotient.div_rem(&BigUint::from(2));
+
+        if !bit.is_zero() {
+            product += &multiple;
+        }
+
+        multiple = &multiple * 2;
+        quotient = half;
+    }
+
+    assert_eq!(product + &remainder, dividend);
+}
@@ -0,0 +1,56 @@
+use super::parse;
+use crate::graph::search::Captures;
+
+#[test]
+fn test_replace() {
+    let graph = parse("a+");
+
+    assert_eq!(graph.replace("baaab aab", "x"), "bxb aab");
+    assert_eq!(graph.replace_all("baaab aab", "x"), "bxb xb");
+    assert_eq!(graph.replacen("a-a-a-a", 2, "x"), "x-x-a-a");
+    assert_eq!(graph.replace_all("bbb", "x"), "bbb");
+}
+
+#[test]
+fn test_templates() {
+    let graph = parse(r"(?<first>\w+) (?<last>\w+)");
+
+    let cases = [
+        ("$2, $1", "Lovelace, Ada"),
+        ("${last}, ${first}", "Lovelace, Ada"),
+        ("$last_name", ""),
+        ("${2}_$1", "Lovelace_Ada"),
+        ("$$1 costs $", "$1 costs $"),
+        ("$3${nope}", ""),
+        ("${1", "${1"),
+    ];
+
+    for (template, expected) in cases {
+        assert_eq!(
+            graph.replace("Ada Lovelace", template),
+            expected,
+            "{template}"
+        );
+    }
+}
+
+#[test]
+fn test_closure() {
+    let graph = parse(r"\d+");
+
+    let doubled = graph.replace_all("1 + 20 = 21", |captures: &Captures| {
+        let number: u32 = captures.get(0).unwrap().as_str().parse().unwrap();
+
+        (number * 2).to_string()
+    });
+
+    assert_eq!(doubled, "2 + 40 = 42");
+}
+
+#[test]
+fn test_empty_matches() {
+    let graph = parse("x*");
+
+    assert_eq!(graph.replace_all("abc", "-"), "-a-b-c-");
+    assert_eq!(graph.replace_all("axxb", "-"), "-a-b-");
+}
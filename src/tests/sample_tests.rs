@@ -0,0 +1,92 @@
+use super::parse;
+use crate::big_uint::BigUint;
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::HashMap;
+
+#[test]
+fn test_sample_matches() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for pattern in ["a*b+", r"(\d|x)+-?", "((ab?|c?d)+|e+)+", "[α-ω]{3,}|.z"] {
+        let graph = parse(pattern);
+
+        for len in 0..8 {
+            let expected = !graph.count_strings(len).is_zero();
+
+            for _ in 0..10 {
+                let sample = graph.sample(len, &mut rng);
+
+                assert_eq!(sample.is_some(), expected, "{pattern} at length {len}");
+
+                if let Some(sample) = sample {
+                    assert_eq!(sample.chars().count(), len);
+                    assert!(graph.matches(&sample), "{pattern} should match {sample:?}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_sample_none() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    assert_eq!(parse("ab").sample(3, &mut rng), None);
+    assert_eq!(parse("a").intersect(parse("b")).sample(1, &mut rng), None);
+    assert_eq!(parse("").sample(0, &mut rng).as_deref(), Some(""));
+}
+
+#[test]
+fn test_sample_uniform() {
+    let mut rng = StdRng::seed_from_u64(1);
+
+    // "ac" can be matched two ways, but should be no more likely than "bc" or "ad".
+    let graph = parse("(a|b)c|a[cd]");
+
+    let mut counts = HashMap::new();
+
+    for _ in 0..3000 {
+        *counts
+            .entry(graph.sample(2, &mut rng).unwrap())
+            .or_insert(0) += 1;
+    }
+
+    assert_eq!(counts.len(), 3);
+
+    for (string, count) in counts {
+        assert!(
+            (850..1150).contains(&count),
+            "{string} sampled {count} times"
+        );
+    }
+}
+
+#[test]
+fn test_sample_reproducible() {
+    let graph = parse("[a-z]{40}");
+
+    let samples = |seed| {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        (0..5)
+            .map(|_| graph.sample(40, &mut rng).unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(samples(7), samples(7));
+    assert_ne!(samples(7), samples(8));
+}
+
+#[test]
+fn test_random_below() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let bound = parse("[a-z]*").count_strings(20);
+
+    for _ in 0..100 {
+        assert!(BigUint::random_below(&bound, &mut rng) < bound);
+    }
+
+    for _ in 0..100 {
+        assert!(BigUint::random_below(&BigUint::from(3), &mut rng) < BigUint::from(3));
+    }
+}
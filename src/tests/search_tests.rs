@@ -0,0 +1,170 @@
+use super::parse;
+use crate::graph::Graph;
+
+fn do_test(pattern: &str, haystack: &str, expected: &[&str]) {
+    let graph = parse(pattern);
+
+    let actual: Vec<_> = graph
+        .find_iter(haystack)
+        .map(|found| found.as_str())
+        .collect();
+
+    assert_eq!(
+        actual, expected,
+        "pattern: {pattern}, haystack: {haystack:?}"
+    );
+}
+
+#[test]
+fn test_find() {
+    let graph = parse("[0-9]+");
+
+    let found = graph.find("abc 123 456").unwrap();
+
+    assert_eq!((found.start(), found.end()), (4, 7));
+    assert_eq!(found.as_str(), "123");
+    assert!(graph.find("abc").is_none());
+}
+
+#[test]
+fn test_leftmost_first() {
+    // leftmost wins over longest.
+    do_test("b+|ab", "abbb", &["ab", "bb"]);
+
+    // earlier alternatives win over longer ones.
+    do_test("a|ab", "ab", &["a"]);
+    do_test("ab|a", "ab", &["ab"]);
+
+    // quantifiers are greedy.
+    do_test("a+", "aaa", &["aaa"]);
+    do_test("a?a", "aa", &["aa"]);
+    do_test("(a|b)*b", "abab!", &["abab"]);
+}
+
+#[test]
+fn test_find_iter() {
+    do_test(r"\w+", "hello, wide world!", &["hello", "wide", "world"]);
+    do_test("lo", "lolol", &["lo", "lo"]);
+}
+
+#[test]
+fn test_empty_matches() {
+    do_test("a*", "baaab", &["", "aaa", ""]);
+    do_test("", "ab", &["", "", ""]);
+}
+
+#[test]
+fn test_multi_byte() {
+    let graph = parse("é+");
+    let matches: Vec<_> = graph
+        .find_iter("aéébé")
+        .map(|found| found.range())
+        .collect();
+
+    assert_eq!(matches, [1..5, 6..8]);
+
+    do_test("x*", "é", &["", ""]);
+}
+
+/// Check the text captured by each group of the first match.
+fn do_captures_test(pattern: &str, haystack: &str, expected: &[Option<&str>]) {
+    let graph = parse(pattern);
+    let captures = graph.captures(haystack).unwrap();
+
+    let actual: Vec<_> = captures
+        .iter()
+        .map(|group| group.map(|found| found.as_str()))
+        .collect();
+
+    assert_eq!(
+        actual, expected,
+        "pattern: {pattern}, haystack: {haystack:?}"
+    );
+}
+
+#[test]
+fn test_captures() {
+    do_captures_test(
+        r"(\d+)-(\d+)",
+        "tel: 555-1234",
+        &[Some("555-1234"), Some("555"), Some("1234")],
+    );
+
+    // groups are numbered by their opening parenthesis.
+    do_captures_test(
+        "((a)(b))(c)",
+        "abc",
+        &[Some("abc"), Some("ab"), Some("a"), Some("b"), Some("c")],
+    );
+
+    // non-participating groups are absent.
+    do_captures_test("(a)|(b)", "b", &[Some("b"), None, Some("b")]);
+
+    // non-capturing groups are not counted.
+    do_captures_test("(?:a)(b)", "ab", &[Some("ab"), Some("b")]);
+}
+
+#[test]
+fn test_captures_repetition() {
+    // a repeated group captures its last iteration.
+    do_captures_test("(a|b)+", "abba", &[Some("abba"), Some("a")]);
+
+    // greedy and nested repetition.
+    do_captures_test("(a*)(a*)", "aaa", &[Some("aaa"), Some("aaa"), Some("")]);
+    do_captures_test("((a)|b)*", "ab", &[Some("ab"), Some("b"), Some("a")]);
+}
+
+#[test]
+fn test_named_captures() {
+    let graph = parse(r"(?<year>\d\d\d\d)-(?P<month>\d\d)");
+    let captures = graph.captures("on 2024-06-01").unwrap();
+
+    assert_eq!(captures.name("year").unwrap().as_str(), "2024");
+    assert_eq!(captures.name("month").unwrap().range(), 8..10);
+    assert!(captures.name("day").is_none());
+}
+
+#[test]
+fn test_group_combinator() {
+    let graph = Graph::from('a')
+        .one_or_more()
+        .group(Some("as"))
+        .concat(Graph::from('b').group(None));
+
+    let captures: Vec<_> = graph
+        .captures_iter("aab ab b")
+        .map(|captures| {
+            (
+                captures.name("as").unwrap().as_str(),
+                captures.get(2).unwrap().range(),
+            )
+        })
+        .collect();
+
+    assert_eq!(captures, [("aa", 2..3), ("a", 5..6)]);
+}
+
+#[test]
+fn test_captures_bounded_repetition() {
+    // every copy of a repeated group captures into the same group.
+    do_captures_test(r"(\d),{2}", "1,,", &[Some("1,,"), Some("1")]);
+    do_captures_test(
+        "(?:(a)|(b)){3}",
+        "aba",
+        &[Some("aba"), Some("a"), Some("b")],
+    );
+    do_captures_test("(a|b){2,}", "abb", &[Some("abb"), Some("b")]);
+}
+
+#[test]
+fn test_captures_zero_repetition() {
+    // a group repeated zero times still counts towards the numbering, but never captures.
+    do_captures_test("(a){0}(b)", "b", &[Some("b"), None, Some("b")]);
+
+    let graph = parse("(?<skipped>a){0}(?<kept>b)");
+    let captures = graph.captures("ab").unwrap();
+
+    assert_eq!(captures.len(), 3);
+    assert!(captures.name("skipped").is_none());
+    assert_eq!(captures.name("kept").unwrap().range(), 1..2);
+}
@@ -0,0 +1,52 @@
+use super::parse;
+
+#[test]
+fn test_split() {
+    let graph = parse(r"\s*[,;]\s*");
+
+    let pieces: Vec<_> = graph.split("a, b;c ,, d").collect();
+    assert_eq!(pieces, ["a", "b", "c", "", "d"]);
+
+    let pieces: Vec<_> = graph.split(",a,").collect();
+    assert_eq!(pieces, ["", "a", ""]);
+
+    let pieces: Vec<_> = graph.split("").collect();
+    assert_eq!(pieces, [""]);
+}
+
+#[test]
+fn test_splitn() {
+    let graph = parse(" +");
+    let line = "2024-06-01 12:00:00 WARN disk  almost full";
+
+    let pieces: Vec<_> = graph.splitn(line, 4).collect();
+    assert_eq!(
+        pieces,
+        ["2024-06-01", "12:00:00", "WARN", "disk  almost full"]
+    );
+
+    let pieces: Vec<_> = graph.splitn("a b", 10).collect();
+    assert_eq!(pieces, ["a", "b"]);
+
+    assert_eq!(graph.splitn("a b", 1).collect::<Vec<_>>(), ["a b"]);
+    assert_eq!(graph.splitn("a b", 0).count(), 0);
+}
+
+#[test]
+fn test_split_inclusive() {
+    let graph = parse("\n");
+
+    let lines: Vec<_> = graph.split_inclusive("one\ntwo\n\nthree").collect();
+    assert_eq!(lines, ["one\n", "two\n", "\n", "three"]);
+
+    let lines: Vec<_> = graph.split_inclusive("one\n").collect();
+    assert_eq!(lines, ["one\n"]);
+}
+
+#[test]
+fn test_split_empty_matches() {
+    let graph = parse("");
+
+    let pieces: Vec<_> = graph.split("abc").collect();
+    assert_eq!(pieces, ["", "a", "b", "c", ""]);
+}
@@ -0,0 +1,104 @@
+use super::parse;
+use crate::{
+    graph::{evaluate::Evaluate, Graph},
+    state::{State, StateSet},
+};
+use std::collections::BTreeSet;
+
+/// Check every state of the [Graph] is numbered below its state count, and every number is used.
+fn assert_dense(graph: &Graph) {
+    let states: BTreeSet<_> = graph
+        .rules()
+        .flat_map(|rule| [rule.start(), rule.end()])
+        .chain([*graph.start(), *graph.end()])
+        .map(|state| state.index())
+        .collect();
+
+    assert_eq!(
+        states,
+        (0..graph.state_count()).collect(),
+        "{}",
+        graph.label()
+    );
+}
+
+#[test]
+fn test_dense_states() {
+    for pattern in [
+        "",
+        "a",
+        "ab|c",
+        "(?<x>a(b)*)+",
+        "[a-z]{2,4}",
+        "(a|b)*c?d{3,}",
+    ] {
+        assert_dense(&parse(pattern));
+    }
+
+    assert_dense(&parse("a*b").intersect(parse("a+b*")));
+    assert_dense(&parse("(ab)*").concat(parse("ab").clone()));
+}
+
+#[test]
+fn test_states_numbered_per_graph() {
+    // building other graphs first doesn't change the numbering.
+    let first = parse("a");
+    let _ = parse("bcd|efg");
+    let second = parse("a");
+
+    assert_eq!(first.start(), second.start());
+    assert_eq!(first.state_count(), 2);
+}
+
+#[test]
+fn test_deterministic_debug() {
+    let dot = format!("{:?}", parse("(a|bc)*"));
+
+    assert_eq!(format!("{:?}", parse("(a|bc)*")), dot);
+    assert!(dot.contains("S0 -> S1"));
+}
+
+#[test]
+fn test_state_set() {
+    let mut set = StateSet::new(8);
+
+    assert!(set.insert(State::new(5)));
+    assert!(set.insert(State::new(2)));
+    assert!(!set.insert(State::new(5)));
+
+    assert!(set.contains(State::new(2)));
+    assert!(!set.contains(State::new(3)));
+    assert_eq!(
+        set.iter().collect::<Vec<_>>(),
+        [State::new(5), State::new(2)]
+    );
+
+    set.clear();
+
+    assert!(set.is_empty());
+    assert!(!set.contains(State::new(5)));
+
+    // stale positions left by clearing aren't mistaken for members.
+    assert!(set.insert(State::new(2)));
+    assert!(!set.contains(State::new(5)));
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn test_evaluate_current_states() {
+    let graph = parse("ab|a(c|d)*");
+
+    let evaluate = Evaluate::new(&graph).try_follow_rules('a').unwrap();
+    let states: BTreeSet<_> = evaluate.current_states().collect();
+
+    assert_eq!(states.len(), evaluate.state_count());
+    assert!(states.contains(graph.end()));
+    assert!(evaluate.is_in_end_state());
+
+    // stepping reuses the same sets without carrying states over.
+    let evaluate = evaluate.try_follow_rules('b').unwrap();
+
+    assert!(evaluate.is_in_end_state());
+    assert!(evaluate.state_count() < states.len());
+    assert!(evaluate.try_follow_rules('b').is_none());
+}
@@ -1,4 +1,5 @@
-use crate::graph::Graph;
+use super::parse;
+use crate::{char_class::CharClass, graph::Graph};
 
 fn do_test(graph: &Graph, max_len: usize, expected: &[&str]) {
     type Strings = std::collections::HashSet<String>;
@@ -47,3 +48,137 @@ fn test_1() {
         ],
     );
 }
+
+#[test]
+fn test_intersect() {
+    let graph = {
+        let even_as = parse("(b*ab*a)*b*");
+        let ends_with_ab = parse("(a|b)*ab");
+
+        even_as.intersect(ends_with_ab)
+    };
+
+    do_test(&graph, 4, &["aab", "abab", "baab"]);
+}
+
+#[test]
+fn test_derived_alphabet() {
+    let graph = parse("[A-C]9|é");
+
+    assert_eq!(
+        graph.alphabet(),
+        CharClass::new(['A'..='C', '9'..='9', 'é'..='é'])
+    );
+
+    do_test(&graph, 2, &["A9", "B9", "C9", "é"]);
+}
+
+#[test]
+fn test_produce_strings_with() {
+    let graph = parse(r"\d+|x.");
+    let strings: Vec<_> = graph
+        .produce_strings_with(CharClass::new(['0'..='1', 'x'..='x']), 2)
+        .collect();
+
+    assert_eq!(
+        strings,
+        ["0", "00", "01", "1", "10", "11", "x0", "x1", "xx"]
+    );
+
+    // characters outside the alphabet are never produced, even if the graph would match them.
+    assert_eq!(
+        graph
+            .produce_strings_with(CharClass::single('a'), 3)
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn test_size_hint() {
+    let graph = parse("[ab]*");
+
+    assert_eq!(graph.produce_strings(3).size_hint(), (0, Some(15)));
+    assert_eq!(
+        graph
+            .produce_strings_with(CharClass::new(['a'..='c']), 2)
+            .size_hint(),
+        (0, Some(13))
+    );
+    assert_eq!(graph.produce_strings(3).count(), 15);
+}
+
+#[test]
+fn test_shortlex() {
+    let graph = parse("a*b*");
+    let strings: Vec<_> = graph.produce_shortlex(7).take(7).collect();
+
+    assert_eq!(strings, ["", "a", "b", "aa", "ab", "bb", "aaa"]);
+
+    for pattern in ["(lo)+l+", "((ab?|c?d)+|e+)+", "[x-z]|[a-c]{2}", "hah+a*"] {
+        let graph = parse(pattern);
+
+        let mut expected: Vec<_> = graph.produce_strings(5).collect();
+        expected.sort_by_key(|string| (string.chars().count(), string.clone()));
+
+        assert_eq!(
+            graph.produce_shortlex(5).collect::<Vec<_>>(),
+            expected,
+            "{pattern}"
+        );
+    }
+}
+
+#[test]
+fn test_shortlex_with_alphabet() {
+    let graph = parse(r"\d+");
+    let strings: Vec<_> = graph
+        .produce_shortlex_with(CharClass::new(['0'..='1']), 2)
+        .collect();
+
+    assert_eq!(strings, ["0", "1", "00", "01", "10", "11"]);
+}
+
+#[test]
+fn test_shortlex_stops_early() {
+    // there are no strings longer than 2 characters, so the remaining lengths needn't be tried.
+    let graph = parse("ab|c");
+
+    assert_eq!(
+        graph.produce_shortlex(10_000).collect::<Vec<_>>(),
+        ["c", "ab"]
+    );
+}
+
+#[test]
+fn test_produce_non_matching() {
+    let graph = parse(r"\d+");
+    let strings: Vec<_> = graph.produce_non_matching(2).collect();
+
+    // near misses of "0" first, then of "00", then everything else.
+    assert_eq!(strings, ["", "A", "A0", "0A", "AA"]);
+}
+
+#[test]
+fn test_non_matching_near_misses_first() {
+    let graph = parse("(ab|cd)+x?");
+    let strings: Vec<_> = graph.produce_non_matching(4).collect();
+
+    // the strings of a, b, c, d, x and one other character, less the 8 matches.
+    assert_eq!(strings.len(), 1 + 6 + 36 + 216 + 1296 - 8);
+
+    // no string is produced twice or matches.
+    assert_eq!(
+        strings
+            .iter()
+            .collect::<std::collections::HashSet<_>>()
+            .len(),
+        strings.len()
+    );
+    assert!(strings
+        .iter()
+        .all(|string| !graph.matches(string) && string.chars().count() <= 4));
+
+    // the first strings are one edit away from "ab", the shortest match.
+    assert_eq!(strings[..6], ["b", "0b", "bb", "cb", "db", "xb"]);
+}